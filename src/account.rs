@@ -2,36 +2,44 @@
 
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::common::{Address, Amount, Currency};
-use crate::payment_method::PaymentMethod;
+use crate::common::{open_enum, Address, Amount, Currency};
+use crate::error::ValidationError;
+use crate::id::AccountId;
+use crate::limits::SrnLimit;
+use crate::payment_method::{PaymentMethod, PaymentMethodStatus};
+use crate::srn::SystemResourceName;
 
 /// See [Get Master Account - Result Format](https://docs.sendwyre.com/docs/get-master-account#result-format)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct MasterAccount {
-    pub id: String,
+    pub id: AccountId,
     pub srn: String,
     pub created_at: u64,
     pub updated_at: Option<u64>,
     pub deleted_at: Option<u64>,
     pub disabled_at: Option<u64>,
     pub locked_at: Option<u64>,
-    // pub locked_reason: ???,
+    pub locked_reason: Option<String>,
     pub under_review_at: Option<u64>,
+    pub review_reason: Option<String>,
     pub in_review_at: Option<u64>,
     pub compliance_approved_at: Option<u64>,
     pub status: AccountStatus,
     // pub stripe_account_id: ???,
     pub profile: MasterAccountProfile,
     pub payment_methods: Vec<PaymentMethod>,
-    // pub identities: Vec<???>,
+    #[serde(default)]
+    pub identities: Vec<Identity>,
     pub deposit_addresses: HashMap<Currency, String>, // currency => adddress
     // pub ledgers: Vec<???>,
     // pub documents: Vec<???>,
-    // pub srn_limits: Vec<???>,
+    #[serde(default)]
+    pub srn_limits: Vec<SrnLimit>,
     // pub cellphone: ???,
     pub pusher_channel: String,
     pub email: String,
@@ -41,15 +49,70 @@ pub struct MasterAccount {
     // pub lastLoginLocation: ???,
     // pub loc: ???,
     // pub email_identity: ???,
-    // pub total_balances: ???,
-    // pub available_balances: ???,
+    pub total_balances: HashMap<Currency, Amount>,
+    pub available_balances: HashMap<Currency, Amount>,
     pub verified: bool,
     #[serde(rename = "type")]
     pub kind: String,
 }
 
+impl MasterAccount {
+    /// Whether the account is locked or under review, and why. A lock takes
+    /// precedence over a review.
+    #[must_use]
+    pub fn lock_status(&self) -> LockStatus {
+        if let Some(since) = self.locked_at {
+            return LockStatus::Locked {
+                since,
+                reason: self.locked_reason.clone(),
+            };
+        }
+
+        match self.under_review_at {
+            Some(since) => LockStatus::UnderReview {
+                since,
+                reason: self.review_reason.clone(),
+            },
+            None => LockStatus::Unlocked,
+        }
+    }
+
+    /// The payment methods that are `ACTIVE` and not disabled, i.e. usable
+    /// as a transfer source or destination.
+    pub fn active_payment_methods(&self) -> impl Iterator<Item = &PaymentMethod> {
+        self.payment_methods.iter().filter(|payment_method| {
+            payment_method.status == PaymentMethodStatus::Active && !payment_method.disabled
+        })
+    }
+}
+
+/// See [`MasterAccount::lock_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockStatus {
+    /// The account can transact.
+    Unlocked,
+
+    /// Wyre is reviewing the account.
+    UnderReview {
+        /// When the review started, in milliseconds since the epoch.
+        since: u64,
+
+        /// Why the account is being reviewed, if Wyre said.
+        reason: Option<String>,
+    },
+
+    /// The account is locked, the reason determines how support can unlock it.
+    Locked {
+        /// When the account was locked, in milliseconds since the epoch.
+        since: u64,
+
+        /// Why the account was locked, if Wyre said.
+        reason: Option<String>,
+    },
+}
+
 /// See [Get Master Account - Result Format](https://docs.sendwyre.com/docs/get-master-account#result-format)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct MasterAccountProfile {
@@ -68,7 +131,7 @@ pub struct MasterAccountProfile {
     pub notify_cellphone: bool,
     // pub notify_apns_device: ???,
     pub onboarding_dashboard_completed: bool,
-    pub display_currency: String,
+    pub display_currency: Currency,
     // pub cpf_number: ???,
     #[serde(rename = "type")]
     pub kind: String,
@@ -84,11 +147,11 @@ pub struct MasterAccountProfile {
     // pub name: ???
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct Account {
-    pub id: String,
+    pub id: AccountId,
     pub status: AccountStatus,
     #[serde(rename = "type")]
     pub kind: AccountType,
@@ -99,10 +162,54 @@ pub struct Account {
     pub total_balances: HashMap<Currency, Amount>,
     pub available_balances: HashMap<Currency, Amount>,
     pub profile_fields: Vec<ProfileField>,
+    #[serde(default)]
+    pub documents: Vec<AccountDocument>,
+    #[serde(default)]
+    pub identities: Vec<Identity>,
+}
+
+impl Account {
+    /// The SRN of the account, e.g. for masquerading as it.
+    #[must_use]
+    pub fn srn(&self) -> SystemResourceName {
+        SystemResourceName::Account(self.id.to_string())
+    }
+
+    /// The fields of `desired` whose value differs from the account's current
+    /// value for that field (or that the account doesn't have yet), i.e. the
+    /// minimal [`UpdateAccount`] that gets the account to `desired`. Fields
+    /// Wyre doesn't echo back, like SSNs, are always included.
+    #[must_use]
+    pub fn diff_fields(&self, desired: &[CreateProfileField]) -> Vec<CreateProfileField> {
+        desired
+            .iter()
+            .filter(|field| {
+                !self.profile_fields.iter().any(|current| {
+                    current.field_id == field.field_id && current.value == field.value
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The fields Wyre sent back `OPEN` with a note, i.e. a correctable
+    /// problem the account holder should be prompted to fix, along with the
+    /// note.
+    #[must_use]
+    pub fn rejected_fields(&self) -> Vec<(&ProfileFieldId, &str)> {
+        self.profile_fields
+            .iter()
+            .filter(|field| field.status == ProfileFieldStatus::Open)
+            .filter_map(|field| match field.note.as_deref() {
+                Some(note) if !note.is_empty() => Some((&field.field_id, note)),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 /// See [Create Account - Parameters](https://docs.sendwyre.com/docs/create-account#parameters).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateAccount {
     /// The type of account, currently `INDIVIDUAL` is the only supported value.
@@ -121,46 +228,126 @@ pub struct CreateAccount {
     /// Supply your own Account ID when creating noncustodial accounts. This
     /// field is used to track which account referred the new account into our
     /// system.
-    pub referrer_account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrer_account_id: Option<AccountId>,
 
     /// When true, the newly created account will be a custodial subaccount
     /// owner by the caller. Otherwise, the account will be a standalone
     /// non-custodial account. (Defaults to `true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subaccount: Option<bool>,
 
     /// if true prevents all outbound emails to the account. This includes all
     /// communications listed [here](https://docs.sendwyre.com/docs/customer-emails-messaging)
-    /// (defaults to `false`).
+    /// (defaults to `false`). Wyre has no API to resend these emails later,
+    /// that has to go through the dashboard or support.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_email: Option<bool>,
 }
 
+impl CreateAccount {
+    /// An `INDIVIDUAL` account with the minimal set of fields Wyre needs to
+    /// review it: legal name, residence address, cellphone number, email,
+    /// date of birth (`YYYY-MM-DD`) and SSN.
+    #[must_use]
+    pub fn minimal_individual(
+        country: &str,
+        name: &str,
+        address: Address,
+        cellphone: &str,
+        email: &str,
+        date_of_birth: &str,
+        ssn: SecretString,
+    ) -> CreateAccount {
+        let field = |field_id, value| CreateProfileField { field_id, value };
+
+        CreateAccount {
+            kind: AccountType::Individual,
+            country: country.to_owned(),
+            profile_fields: vec![
+                field(
+                    ProfileFieldId::IndividualLegalName,
+                    ProfileFieldType::String(Some(name.to_owned())),
+                ),
+                field(
+                    ProfileFieldId::IndividualResidenceAddress,
+                    ProfileFieldType::Address(Some(address)),
+                ),
+                field(
+                    ProfileFieldId::IndividualCellphoneNumber,
+                    ProfileFieldType::Cellphone(Some(cellphone.to_owned())),
+                ),
+                field(
+                    ProfileFieldId::IndividualEmail,
+                    ProfileFieldType::Email(Some(email.to_owned())),
+                ),
+                field(
+                    ProfileFieldId::IndividualDateOfBirth,
+                    ProfileFieldType::Date(Some(date_of_birth.to_owned())),
+                ),
+                field(ProfileFieldId::IndividualSsn, ProfileFieldType::Ssn(ssn)),
+            ],
+            referrer_account_id: None,
+            subaccount: None,
+            disable_email: None,
+        }
+    }
+
+    /// Checks the request for problems that the Wyre API would otherwise
+    /// reject after it has been sent.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.kind == AccountType::Business {
+            return Err(ValidationError(
+                "BUSINESS accounts can't be created through the API, none of the business \
+                 profile fields are supported"
+                    .to_owned(),
+            ));
+        }
+
+        for field in &self.profile_fields {
+            if field.field_id == ProfileFieldId::IndividualSsn
+                && !matches!(
+                    field.value,
+                    ProfileFieldType::String(_) | ProfileFieldType::Ssn(_)
+                )
+            {
+                return Err(ValidationError(
+                    "individualSsn must be submitted as a STRING field".to_owned(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// See [Update Account - Parameters](https://docs.sendwyre.com/docs/submit-account-info#parameters).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateAccount {
     /// An array containing objects of fieldIds and values.
     pub profile_fields: Vec<CreateProfileField>,
 }
 
-/// See [Account Status](https://docs.sendwyre.com/docs/account-resource#account-status)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum AccountStatus {
-    /// Waiting on action from you or the Account holder. This is the initial
-    /// state before all information has been submitted, or after any has
-    /// failed to pass verifications.
-    Open,
-
-    /// Information has been fully submitted and is waiting on review from
-    /// Wyre. The Account cannot yet transact.
-    Pending,
-
-    /// Information has been reviewed and accepted by Wyre. The Account is now
-    /// approved to transact.    
-    Approved,
-
-    /// The Account has been closed and may not transact.
-    Closed,
+open_enum! {
+    /// See [Account Status](https://docs.sendwyre.com/docs/account-resource#account-status)
+    pub enum AccountStatus {
+        /// Waiting on action from you or the Account holder. This is the initial
+        /// state before all information has been submitted, or after any has
+        /// failed to pass verifications.
+        Open = "OPEN",
+
+        /// Information has been fully submitted and is waiting on review from
+        /// Wyre. The Account cannot yet transact.
+        Pending = "PENDING",
+
+        /// Information has been reviewed and accepted by Wyre. The Account is now
+        /// approved to transact.
+        Approved = "APPROVED",
+
+        /// The Account has been closed and may not transact.
+        Closed = "CLOSED",
+    }
 }
 
 /// See [Account Types](https://docs.sendwyre.com/docs/account-resource#account-types)
@@ -177,7 +364,7 @@ pub enum AccountType {
 }
 
 /// See [Account Fields](https://docs.sendwyre.com/docs/account-resource#account-fields)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProfileField {
     /// The specific datapoint encapsulated by the field.
@@ -198,7 +385,7 @@ pub struct ProfileField {
 }
 
 /// See [Account Fields](https://docs.sendwyre.com/docs/account-resource#account-fields)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateProfileField {
     /// The specific datapoint encapsulated by the field.
@@ -209,20 +396,20 @@ pub struct CreateProfileField {
     pub value: ProfileFieldType,
 }
 
-/// See [Field Statuses](https://docs.sendwyre.com/docs/account-resource#field-statuses)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ProfileFieldStatus {
-    /// Waiting on action from you or the Account holder. This is the initial
-    /// state before any information has been submitted, or after it has failed
-    /// to pass verifications.
-    Open,
+open_enum! {
+    /// See [Field Statuses](https://docs.sendwyre.com/docs/account-resource#field-statuses)
+    pub enum ProfileFieldStatus {
+        /// Waiting on action from you or the Account holder. This is the initial
+        /// state before any information has been submitted, or after it has failed
+        /// to pass verifications.
+        Open = "OPEN",
 
-    /// Information has been fully submitted and is waiting on review from Wyre.
-    Pending,
+        /// Information has been fully submitted and is waiting on review from Wyre.
+        Pending = "PENDING",
 
-    /// Information has been reviewed and accepted by Wyre.
-    Approved,
+        /// Information has been reviewed and accepted by Wyre.
+        Approved = "APPROVED",
+    }
 }
 
 /// See [Field Types](https://docs.sendwyre.com/docs/account-resource#field-types)
@@ -236,6 +423,17 @@ pub enum ProfileFieldType {
     /// A basic string.
     String(Option<String>),
 
+    /// A social security number, sent as a `STRING` field. The value is kept
+    /// in a [`SecretString`] so it's redacted from `Debug` output. Responses
+    /// never contain this variant, SSN fields are returned as
+    /// [`ProfileFieldType::String`].
+    #[serde(
+        rename = "STRING",
+        serialize_with = "serialize_secret",
+        skip_deserializing
+    )]
+    Ssn(SecretString),
+
     /// A full cellphone number including country code (e.g. `+15554445555`).
     Cellphone(Option<String>),
 
@@ -255,7 +453,9 @@ pub enum ProfileFieldType {
     /// All document IDs uploaded will be retained by the field until review.
     /// During review, invalid/unacceptable document IDs will be deleted. Once
     /// the field status is `APPROVED`, then only the actually approved
-    /// document IDs shall remain.
+    /// document IDs shall remain. A `null` value (no documents uploaded yet)
+    /// is read as an empty list.
+    #[serde(deserialize_with = "deserialize_documents")]
     Document(Vec<String>),
 
     /// A Payment Method record. Payment methods are created using our Payment
@@ -264,8 +464,39 @@ pub enum ProfileFieldType {
     PaymentMethod(Option<String>),
 }
 
+impl PartialEq for ProfileFieldType {
+    fn eq(&self, other: &Self) -> bool {
+        use ProfileFieldType::*;
+
+        match (self, other) {
+            (String(a), String(b)) => a == b,
+            (Ssn(a), Ssn(b)) => a.expose_secret() == b.expose_secret(),
+            (Cellphone(a), Cellphone(b)) => a == b,
+            (Email(a), Email(b)) => a == b,
+            (Address(a), Address(b)) => a == b,
+            (Date(a), Date(b)) => a == b,
+            (Document(a), Document(b)) => a == b,
+            (PaymentMethod(a), PaymentMethod(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+fn serialize_secret<S: Serializer>(
+    secret: &SecretString,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(secret.expose_secret())
+}
+
+fn deserialize_documents<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    Ok(Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// See [Field IDs](https://docs.sendwyre.com/v3/docs/account-resource#field-ids)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ProfileFieldId {
     /// The full legal name of the account holder (corresponding value must be
@@ -293,7 +524,7 @@ pub enum ProfileFieldId {
     IndividualDateOfBirth,
 
     /// The account holder's social security number (corresponding value must
-    /// be [`ProfileFieldType::String`]).
+    /// be [`ProfileFieldType::Ssn`] or [`ProfileFieldType::String`]).
     IndividualSsn,
 
     /// A payment method that the account holder owns (corresponding value must
@@ -365,8 +596,94 @@ pub struct UploadDocument<D> {
     pub content_type: String,
 }
 
+impl<D> UploadDocument<D> {
+    /// Checks that a side is given for document types that need both sides
+    /// uploaded, since Wyre would otherwise keep waiting for the other side.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match &self.document_type {
+            Some(document_type)
+                if document_type.requires_both_sides() && self.document_sub_type.is_none() =>
+            {
+                Err(ValidationError(format!(
+                    "{:?} documents need a FRONT and a BACK upload, document_sub_type must be set",
+                    document_type
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A document uploaded to an account with [`Client::upload_document`](crate::Client::upload_document).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDocument {
+    /// The id of the document, as referenced by [`ProfileFieldType::Document`].
+    pub id: String,
+
+    /// The type of document, if one was given when uploading it.
+    pub document_type: Option<DocumentType>,
+
+    /// The side of the document, if one was given when uploading it.
+    pub document_sub_type: Option<DocumentSubType>,
+
+    /// Where the document is in Wyre's review.
+    pub status: DocumentStatus,
+
+    /// When the document was uploaded.
+    pub uploaded_at: Option<u64>,
+}
+
+/// A piece of contact or identity information on an account, e.g. its
+/// email address, and whether Wyre has verified it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    /// The SRN of the identity, e.g. `email:test@sendwyre.com`.
+    pub srn: SystemResourceName,
+
+    /// The kind of identity.
+    #[serde(rename = "type")]
+    pub kind: IdentityType,
+
+    /// Whether Wyre has verified the identity.
+    #[serde(default)]
+    pub verified: bool,
+
+    /// When the identity was verified.
+    pub verified_at: Option<u64>,
+}
+
+open_enum! {
+    /// See [`Identity`].
+    pub enum IdentityType {
+        /// An email address.
+        Email = "EMAIL",
+
+        /// A cellphone number.
+        Cellphone = "CELLPHONE",
+
+        /// A social security number.
+        Ssn = "SSN",
+    }
+}
+
+open_enum! {
+    /// See [`AccountDocument`].
+    pub enum DocumentStatus {
+        /// Waiting on review from Wyre.
+        Pending = "PENDING",
+
+        /// Reviewed and accepted by Wyre.
+        Approved = "APPROVED",
+
+        /// Reviewed and rejected by Wyre, a new document has to be uploaded.
+        Rejected = "REJECTED",
+    }
+}
+
 /// See [`UploadDocument`].
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 pub enum DocumentType {
@@ -376,8 +693,17 @@ pub enum DocumentType {
     Passport,
 }
 
+impl DocumentType {
+    /// Returns `true` if both the `FRONT` and the `BACK` have to be uploaded,
+    /// i.e. for everything but `PASSPORT`.
+    #[must_use]
+    pub fn requires_both_sides(&self) -> bool {
+        *self != DocumentType::Passport
+    }
+}
+
 /// See [`UploadDocument`].
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[allow(missing_docs)]
 pub enum DocumentSubType {
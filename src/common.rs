@@ -1,32 +1,197 @@
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use bigdecimal::BigDecimal;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::srn::SystemResourceName;
 
 /// A financial amount (the value is not scaled)
 pub type Amount = BigDecimal;
 
+/// Serializes an optional [`Amount`] in plain decimal notation (`20`, never
+/// `2E+1`), whatever its internal scale. Used by the request bodies.
+pub(crate) fn serialize_plain_amount<S: Serializer>(
+    amount: &Option<Amount>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match amount {
+        Some(amount) if amount.as_bigint_and_exponent().1 < 0 => {
+            serializer.collect_str(&amount.with_scale(0))
+        }
+        Some(amount) => serializer.collect_str(amount),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Defines an enum of the values Wyre documents for a field, with an `Other`
+/// variant that keeps any other value as sent, so that it serializes back
+/// unchanged.
+macro_rules! open_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+
+            /// All other values, contains the value sent by Wyre.
+            Other(String),
+        }
+
+        impl $name {
+            /// The value Wyre uses for the variant.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Other(value),
+                }
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <String as serde::Deserialize>::deserialize(deserializer).map($name::from)
+            }
+        }
+    };
+}
+
+pub(crate) use open_enum;
+
 /// An address
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Address {
     /// A valid street address
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub street1: Option<String>,
 
     /// Additional street address
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub street2: Option<String>,
 
     /// The city name
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub city: Option<String>,
 
     /// A valid state code, it must be two uppercase letter. Ex CA
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<String>,
 
     /// A valid US zipcode
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub postal_code: Option<String>,
 
     /// The country code (alpha-2 country code)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub country: Option<String>,
 }
 
+impl Address {
+    /// Checks the address against the rules Wyre applies to addresses in
+    /// `country` (alpha-2 country code). Only US addresses are currently
+    /// checked, any other country is accepted as is.
+    pub fn validate(&self, country: &str) -> Result<(), AddressValidationError> {
+        use AddressValidationError::*;
+
+        if !country.eq_ignore_ascii_case("US") {
+            return Ok(());
+        }
+
+        if self.street1.is_none() {
+            return Err(MissingField("street1"));
+        }
+
+        if self.city.is_none() {
+            return Err(MissingField("city"));
+        }
+
+        match &self.state {
+            None => return Err(MissingField("state")),
+            Some(state) if state.len() != 2 || !state.chars().all(|c| c.is_ascii_uppercase()) => {
+                return Err(InvalidState(state.clone()))
+            }
+            Some(_) => {}
+        }
+
+        match &self.postal_code {
+            None => return Err(MissingField("postal_code")),
+            Some(postal_code) if !is_us_zipcode(postal_code) => {
+                return Err(InvalidPostalCode(postal_code.clone()))
+            }
+            Some(_) => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` for `12345` and `12345-6789` style zipcodes.
+fn is_us_zipcode(postal_code: &str) -> bool {
+    let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+
+    match postal_code.split_once('-') {
+        Some((zip, plus4)) => {
+            zip.len() == 5 && all_digits(zip) && plus4.len() == 4 && all_digits(plus4)
+        }
+        None => postal_code.len() == 5 && all_digits(postal_code),
+    }
+}
+
+/// Returned by [`Address::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressValidationError {
+    /// A required field wasn't set, contains the field name.
+    MissingField(&'static str),
+
+    /// The state isn't a two letter uppercase code, contains the state.
+    InvalidState(String),
+
+    /// The postal code isn't a valid zipcode, contains the postal code.
+    InvalidPostalCode(String),
+}
+
+impl StdError for AddressValidationError {}
+
+impl Display for AddressValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use AddressValidationError::*;
+
+        match self {
+            MissingField(field) => write!(f, "address is missing {}", field),
+            InvalidState(state) => write!(f, "invalid state code: {}", state),
+            InvalidPostalCode(postal_code) => write!(f, "invalid zipcode: {}", postal_code),
+        }
+    }
+}
+
 /// See [Supported Currencies](https://docs.sendwyre.com/docs/supported-currencies-1)
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Currency {
@@ -167,7 +332,7 @@ pub struct MetadataCallbackPayload {
     pub subscription_id: String,
 
     /// An SRN for the entity that the callback was designated for
-    pub trigger: String,
+    pub trigger: SystemResourceName,
 }
 
 /// See [Webhooks - Callback Urls](https://docs.sendwyre.com/docs/webhooks#callback-urls).
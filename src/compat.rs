@@ -0,0 +1,28 @@
+//! Helpers for driving the client on runtimes built on `futures` 0.1 (e.g.
+//! tokio 0.1). Requires the `compat` feature.
+//!
+//! The methods on [`Client`](crate::Client) are plain `async fn`s and aren't
+//! tied to a particular executor, however the default `reqwest` transport
+//! expects a tokio 1.x reactor to be available while the request is in
+//! flight. Services still running on tokio 0.1 should keep a tokio 1.x
+//! runtime entered (see `tokio::runtime::Runtime::enter`) while driving the
+//! futures returned by [`compat`].
+
+use std::future::Future;
+
+use futures03::compat::Compat;
+use futures03::future::{BoxFuture, FutureExt};
+
+use crate::error::Error;
+
+/// Converts a future returned by the client into a `futures` 0.1 future.
+///
+/// ```ignore
+/// let account = runtime01.block_on(wyre::compat::compat(client.get_master_account()))?;
+/// ```
+pub fn compat<'a, T, F>(future: F) -> Compat<BoxFuture<'a, Result<T, Error>>>
+where
+    F: Future<Output = Result<T, Error>> + Send + 'a,
+{
+    Compat::new(future.boxed())
+}
@@ -0,0 +1,47 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::Arc;
+
+use http::Request;
+
+use crate::redact::redact_body;
+
+/// A description of a request that was built but not sent because the
+/// client is in dry-run mode (see [`Client::with_dry_run`](crate::Client::with_dry_run)).
+#[derive(Debug, Clone)]
+pub struct DryRunRequest {
+    /// The HTTP method, e.g. `POST`
+    pub method: String,
+
+    /// The full url, including the query string
+    pub url: String,
+
+    /// The request body with sensitive values masked, or a placeholder for
+    /// non-JSON bodies (e.g. document uploads).
+    pub body: Option<String>,
+}
+
+impl DryRunRequest {
+    pub(crate) fn new(request: &Request<Vec<u8>>) -> DryRunRequest {
+        let body = request.body();
+
+        DryRunRequest {
+            method: request.method().to_string(),
+            url: request.uri().to_string(),
+            body: if body.is_empty() {
+                None
+            } else {
+                Some(redact_body(body))
+            },
+        }
+    }
+}
+
+/// The callback registered with [`Client::with_dry_run`](crate::Client::with_dry_run).
+#[derive(Clone)]
+pub(crate) struct DryRun(pub(crate) Arc<dyn Fn(&DryRunRequest) + Send + Sync>);
+
+impl Debug for DryRun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "DryRun")
+    }
+}
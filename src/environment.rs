@@ -1,6 +1,10 @@
 use std::convert::TryFrom;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 /// See [Production/Test Environments](https://docs.sendwyre.com/docs/productiontest-environments).
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Environment {
@@ -12,6 +16,15 @@ pub enum Environment {
 }
 
 impl Environment {
+    /// The name of the environment, which [`FromStr`] parses back.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Test => "test",
+            Environment::Production => "production",
+        }
+    }
+
     /// The url used to access the API.
     #[must_use]
     pub fn api_url(&self) -> &str {
@@ -20,6 +33,24 @@ impl Environment {
             Environment::Production => "https://api.sendwyre.com",
         }
     }
+
+    /// The url used to access version 2 of the API.
+    #[must_use]
+    pub fn api_v2_url(&self) -> String {
+        format!("{}/v2", self.api_url())
+    }
+
+    /// The url used to access version 3 of the API.
+    #[must_use]
+    pub fn api_v3_url(&self) -> String {
+        format!("{}/v3", self.api_url())
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
 }
 
 impl FromStr for Environment {
@@ -34,6 +65,23 @@ impl FromStr for Environment {
     }
 }
 
+impl Serialize for Environment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Accepts the same names as [`FromStr`].
+impl<'de> Deserialize<'de> for Environment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|EnvironmentParseError(environment)| {
+                DeError::custom(format!("invalid environment: {}", environment))
+            })
+    }
+}
+
 impl TryFrom<String> for Environment {
     type Error = EnvironmentParseError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
@@ -1,35 +1,222 @@
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-use reqwest::Error as ReqwestError;
-use serde::Deserialize;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::account::Account;
+use crate::payment_method::PaymentMethod;
+use crate::transfer::Transfer;
 
 /// Represents an error that can occur when making an API request.
+///
+/// Non-exhaustive, since some variants depend on the enabled features.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// An error that was reported by the Wyre API
     Api(ApiError),
 
+    /// The response had an error status but its body wasn't an [`ApiError`],
+    /// e.g. an HTML page from a gateway
+    Http {
+        /// The status of the response
+        status: StatusCode,
+
+        /// The body of the response
+        body: String,
+
+        /// The correlation id from the `X-Request-Id` response header, if
+        /// one was sent, see [`ApiError::request_id`]
+        request_id: Option<String>,
+    },
+
+    /// The API key or secret was rejected (`401 Unauthorized` or
+    /// `403 Forbidden`)
+    Unauthorized(ApiError),
+
+    /// The account has been locked for potential fraud reasons
+    /// ([`exception::ACCOUNT_LOCKED`]). The account holder has to contact Wyre
+    /// support, retrying won't help.
+    AccountLocked(ApiError),
+
+    /// The account or IP has been blocked due to detected malicious behavior
+    /// ([`exception::LOCKOUT`]). Retrying won't help.
+    Lockout(ApiError),
+
     /// An error that ocurred during transport
-    Transport(ReqwestError),
+    #[cfg(feature = "reqwest")]
+    Transport(reqwest::Error),
+
+    /// The request couldn't be built, e.g. a header value had invalid
+    /// characters
+    Encode(Box<dyn StdError + Send + Sync>),
+
+    /// The response body couldn't be parsed
+    Decode(serde_json::Error),
+
+    /// Writing the output of an export failed
+    Io(std::io::Error),
+
+    /// An error reported by a custom [`HttpTransport`](crate::HttpTransport)
+    Backend(Box<dyn StdError + Send + Sync>),
+
+    /// The request was rejected locally before being sent to the Wyre API
+    Validation(ValidationError),
+
+    /// The request was built but not sent because the client is in dry-run
+    /// mode (see [`Client::with_dry_run`](crate::Client::with_dry_run))
+    DryRun,
+
+    /// Wyre rejected the payment method that
+    /// [`Client::await_payment_method_active`](crate::Client::await_payment_method_active)
+    /// was waiting on
+    PaymentMethodRejected(Box<PaymentMethod>),
+
+    /// A polling helper gave up waiting, contains the last state that was
+    /// observed
+    Timeout {
+        /// The last state that was observed before timing out
+        last_seen: Box<LastSeen>,
+    },
 }
 
-impl From<ReqwestError> for Error {
-    fn from(error: ReqwestError) -> Self {
+impl Error {
+    /// Returns `true` if the request timed out before a response was
+    /// received, or a polling helper gave up waiting ([`Error::Timeout`]).
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout { .. } => true,
+            #[cfg(feature = "reqwest")]
+            Error::Transport(error) => error.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the credentials were rejected or lack the privilege
+    /// for the request, i.e. a `401`/`403` response or an
+    /// [`exception::ACCESS_DENIED`] error. Usually means `WYRE_API_KEY` or
+    /// `WYRE_API_SECRET` is wrong.
+    pub fn is_unauthorized(&self) -> bool {
+        match self {
+            Error::Unauthorized(_) => true,
+            Error::Http { status, .. } => {
+                *status == StatusCode::UNAUTHORIZED || *status == StatusCode::FORBIDDEN
+            }
+            Error::Api(error) => error.kind == exception::ACCESS_DENIED,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if Wyre asked for an MFA action
+    /// ([`exception::MFA_REQUIRED`]). This shouldn't happen with API keys and
+    /// retrying won't help.
+    pub fn is_mfa_required(&self) -> bool {
+        match self {
+            Error::Api(error) | Error::Unauthorized(error) => error.kind == exception::MFA_REQUIRED,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the error can only be resolved by contacting Wyre
+    /// support, so the request shouldn't be retried.
+    pub fn requires_support(&self) -> bool {
+        match self {
+            Error::AccountLocked(_) | Error::Lockout(_) => true,
+            Error::Api(error) => error.kind == exception::CUSTOMER_SUPPORT,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the request failed while connecting to the Wyre API.
+    pub fn is_connect(&self) -> bool {
+        match self {
+            #[cfg(feature = "reqwest")]
+            Error::Transport(error) => error.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
         Error::Transport(error)
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Decode(error)
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        match error.kind.as_str() {
+            exception::ACCOUNT_LOCKED => Error::AccountLocked(error),
+            exception::LOCKOUT => Error::Lockout(error),
+            _ => Error::Api(error),
+        }
+    }
+}
+
+/// The last state observed by a polling helper before it timed out, see
+/// [`Error::Timeout`].
+// Only ever stored boxed, so the size of the largest variant doesn't matter.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum LastSeen {
+    /// See [`Client::await_transfer_terminal`](crate::Client::await_transfer_terminal).
+    Transfer(Transfer),
+
+    /// See [`Client::await_payment_method_active`](crate::Client::await_payment_method_active).
+    PaymentMethod(PaymentMethod),
+
+    /// See [`Client::update_account_and_await`](crate::Client::update_account_and_await).
+    Account(Account),
+}
+
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Error::Validation(error)
+    }
+}
+
 impl StdError for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{:?}", self)
+        match self {
+            Error::Api(error) => write!(f, "{}", error),
+            Error::Unauthorized(error) => write!(f, "unauthorized: {}", error),
+            Error::AccountLocked(error) => write!(f, "account locked: {}", error),
+            Error::Lockout(error) => write!(f, "locked out: {}", error),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// A request failed local validation, contains a description of the problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError(pub String);
+
+impl StdError for ValidationError {}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
     }
 }
 
 /// See [Errors](https://docs.sendwyre.com/docs/errors)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiError {
     /// A unique identifier for this exception. This is very helpful when
@@ -52,6 +239,45 @@ pub struct ApiError {
     /// In rare cases, an exception may signal `true` here to indicate a
     /// transient problem. This means the request can be safely re-attempted.
     pub transient: bool,
+
+    /// The correlation id from the `X-Request-Id` response header, if Wyre
+    /// sent one. Include it alongside `exception_id` when contacting support.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl StdError for ApiError {}
+
+/// Leads with the exception id (and the request id, if any) so it can be
+/// quoted to support straight from a log line, e.g.
+/// `[EX_123] ValidationException (request REQ_1): Invalid amount`.
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "[{}] {}", self.exception_id, self.kind)?;
+        if let Some(error_code) = &self.error_code {
+            write!(f, "/{}", error_code)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request {})", request_id)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApiError {
+    /// Returns `true` if the request can be retried: Wyre flagged the error as
+    /// transient, the rate limit was hit, or Wyre had an internal problem. An
+    /// MFA requirement is never retryable.
+    pub fn is_retryable(&self) -> bool {
+        if self.kind == exception::MFA_REQUIRED {
+            return false;
+        }
+
+        self.transient || self.kind == exception::RATE_LIMIT || self.kind == exception::UNKNOWN
+    }
 }
 
 /// See [Error Types](https://docs.sendwyre.com/docs/errors#error-types)
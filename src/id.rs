@@ -0,0 +1,77 @@
+//! Strongly typed ids, so that an id of one kind of entity can't be passed
+//! where another is expected.
+
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub String);
+
+        impl $name {
+            /// The id as a string slice.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name(s.to_owned()))
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                $name(id)
+            }
+        }
+
+        impl<'a> From<&'a str> for $name {
+            fn from(id: &'a str) -> Self {
+                $name(id.to_owned())
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+    };
+}
+
+id! {
+    /// The id of an account, e.g. `AC_XXXXXXXX`.
+    AccountId
+}
+
+id! {
+    /// The id of a user, e.g. `US_XXXXXXXX`.
+    UserId
+}
+
+id! {
+    /// The id of a transfer, e.g. `TF_XXXXXXXX`.
+    TransferId
+}
+
+id! {
+    /// The id of a payment method, e.g. `PA_XXXXXXXX`.
+    PaymentMethodId
+}
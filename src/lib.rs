@@ -2,38 +2,103 @@
 //! API.
 //!
 //! Documentation: <https://docs.sendwyre.com/>
+//!
+//! All requests are exposed as `async fn`s on [`Client`]. Applications still
+//! running on tokio 0.1 can enable the `compat` feature and use the helpers
+//! in the `compat` module.
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs, clippy::all)]
 
-use reqwest::StatusCode;
-use reqwest::{Body as ReqwestBody, Client as ReqwestClient};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures03::stream::{self, Stream, StreamExt};
+use http::header::{ACCEPT_LANGUAGE, CONTENT_TYPE};
+use http::StatusCode;
+#[cfg(feature = "reqwest")]
+use reqwest::{Certificate, Client as ReqwestClient, Proxy};
 use secrecy::{ExposeSecret, SecretString};
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
 mod account;
 mod common;
+#[cfg(feature = "compat")]
+pub mod compat;
+mod dry_run;
 mod environment;
 mod error;
+mod id;
+mod limits;
+mod onboarding;
 mod payment_method;
+mod poll;
+mod rate_limit;
+mod redact;
+mod request;
+mod srn;
 mod transfer;
+mod transport;
 mod user;
 
 pub use account::*;
 pub use common::*;
+pub use dry_run::DryRunRequest;
 pub use environment::*;
 pub use error::*;
+pub use id::*;
+pub use limits::*;
+pub use onboarding::*;
 pub use payment_method::*;
+pub use srn::*;
 pub use transfer::*;
+pub use transport::HttpTransport;
 pub use user::*;
 
+use request::RequestBuilder;
+
+/// The response header Wyre uses to correlate a request with its logs.
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The version of the Wyre API the response types are modelled on, sent with
+/// every request unless overridden with [`Client::with_api_version`].
+pub const DEFAULT_API_VERSION: &str = "3";
+
+/// The request header used to pin the response schema.
+const API_VERSION_HEADER: &str = "x-api-version";
+
+/// The most balance requests [`Client::aggregate_balances`] has in flight.
+const AGGREGATE_BALANCES_CONCURRENCY: usize = 4;
+
 /// A client that can be used to access the Wyre API
+///
+/// Requests are made as the account that owns the API key. Wyre has no
+/// partner header, so to act on behalf of a sub-partner or subaccount pass
+/// its SRN as the `masquerade` argument of each call.
 #[derive(Debug, Clone)]
 pub struct Client {
+    #[cfg(feature = "reqwest")]
     http_client: ReqwestClient,
+    transport: Option<Arc<dyn HttpTransport>>,
+    #[cfg(feature = "reqwest")]
+    proxies: Vec<Proxy>,
+    #[cfg(feature = "reqwest")]
+    root_certificates: Vec<Certificate>,
+    #[cfg(feature = "reqwest")]
+    built_in_root_certificates: bool,
     environment: Environment,
     _api_key: SecretString,
     api_secret: SecretString,
+    api_version: String,
+    dry_run: Option<dry_run::DryRun>,
+    request_logging: bool,
+    rate_limiter: Option<Arc<rate_limit::RateLimiter>>,
+    default_masquerade: Option<SystemResourceName>,
+    extra_query: Vec<(String, String)>,
+    language: Option<String>,
 }
 
 impl Client {
@@ -45,13 +110,38 @@ impl Client {
         environment: Environment,
     ) -> Client {
         Client {
+            #[cfg(feature = "reqwest")]
             http_client: ReqwestClient::new(),
+            transport: None,
+            #[cfg(feature = "reqwest")]
+            proxies: Vec::new(),
+            #[cfg(feature = "reqwest")]
+            root_certificates: Vec::new(),
+            #[cfg(feature = "reqwest")]
+            built_in_root_certificates: true,
             environment,
             _api_key: api_key,
             api_secret,
+            api_version: DEFAULT_API_VERSION.to_owned(),
+            dry_run: None,
+            request_logging: false,
+            rate_limiter: None,
+            default_masquerade: None,
+            extra_query: Vec::new(),
+            language: None,
         }
     }
 
+    /// Creates a new client from plain string credentials
+    #[must_use]
+    pub fn new_from_str(api_key: &str, api_secret: &str, environment: Environment) -> Client {
+        Client::new(
+            SecretString::new(api_key.to_owned()),
+            SecretString::new(api_secret.to_owned()),
+            environment,
+        )
+    }
+
     /// Creates a new client from environment variables:
     /// - `WYRE_API_KEY`
     /// - `WYRE_API_SECRET`
@@ -70,179 +160,442 @@ impl Client {
         ))
     }
 
+    /// Loads the variables in the dotenv file at `path`, then creates the
+    /// client like [`Client::from_env`]. Variables that are already set in
+    /// the environment take precedence over the file.
+    #[cfg(feature = "dotenv")]
+    pub fn from_env_file(path: &std::path::Path) -> Result<Client, ClientFromEnvironmentError> {
+        dotenv::from_path(path).map_err(ClientFromEnvironmentError::EnvFile)?;
+
+        Client::from_env()
+    }
+
+    /// Sends `version` in the `X-Api-Version` header instead of
+    /// [`DEFAULT_API_VERSION`].
+    #[must_use]
+    pub fn with_api_version(mut self, version: &str) -> Client {
+        self.api_version = version.to_owned();
+        self
+    }
+
+    /// Sends requests to `environment` instead. Combined with
+    /// [`Clone`] this gives a client for the other environment that shares
+    /// the HTTP client, credentials and rate limit, e.g. to compare a call
+    /// against Test while migrating to Production.
+    #[must_use]
+    pub fn with_environment(mut self, environment: Environment) -> Client {
+        self.environment = environment;
+        self
+    }
+
+    /// Sends requests with `transport` instead of the default `reqwest`
+    /// client. Without the `reqwest` feature there is no default, and every
+    /// request fails with [`Error::Backend`] until a transport is set.
+    #[must_use]
+    pub fn with_transport<T: HttpTransport + 'static>(mut self, transport: T) -> Client {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Routes every request through `proxy`, e.g. a corporate egress proxy.
+    /// Can be called more than once, proxies are tried in the order they were
+    /// added.
+    ///
+    /// Only applies to the default `reqwest` client: a transport installed
+    /// with [`Client::with_transport`] has to be configured on its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Transport`] if a TLS backend cannot be initialized.
+    #[cfg(feature = "reqwest")]
+    #[allow(clippy::result_large_err)]
+    pub fn with_proxy(mut self, proxy: Proxy) -> Result<Client, Error> {
+        self.proxies.push(proxy);
+        self.rebuild_http_client()?;
+        Ok(self)
+    }
+
+    /// Trusts `certificate` as an additional root certificate, e.g. the CA of
+    /// an egress proxy that inspects TLS traffic.
+    ///
+    /// Only applies to the default `reqwest` client: a transport installed
+    /// with [`Client::with_transport`] has to be configured on its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Transport`] if a TLS backend cannot be initialized.
+    #[cfg(feature = "reqwest")]
+    #[allow(clippy::result_large_err)]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Result<Client, Error> {
+        self.root_certificates.push(certificate);
+        self.rebuild_http_client()?;
+        Ok(self)
+    }
+
+    /// Stops trusting the system's root certificates, so only the ones added
+    /// with [`Client::add_root_certificate`] are trusted. This pins the
+    /// client to those certificate authorities.
+    ///
+    /// Only applies to the default `reqwest` client: a transport installed
+    /// with [`Client::with_transport`] has to be configured on its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Transport`] if a TLS backend cannot be initialized.
+    #[cfg(feature = "reqwest")]
+    #[allow(clippy::result_large_err)]
+    pub fn without_built_in_root_certificates(mut self) -> Result<Client, Error> {
+        self.built_in_root_certificates = false;
+        self.rebuild_http_client()?;
+        Ok(self)
+    }
+
+    /// Puts the client in dry-run mode: every request is built and passed to
+    /// `callback` instead of being sent, and the method returns
+    /// [`Error::DryRun`]. Useful for auditing a batch job before running it
+    /// for real.
+    #[must_use]
+    pub fn with_dry_run<F>(mut self, callback: F) -> Client
+    where
+        F: Fn(&DryRunRequest) + Send + Sync + 'static,
+    {
+        self.dry_run = Some(dry_run::DryRun(Arc::new(callback)));
+        self
+    }
+
+    /// Logs every request (method, url and body) at debug level before it is
+    /// sent. SSNs and secret-like values such as tokens are masked.
+    #[must_use]
+    pub fn with_request_logging(mut self) -> Client {
+        self.request_logging = true;
+        self
+    }
+
+    /// Limits the client to `per_second` requests per second. The limit is
+    /// shared by every clone of the returned client, requests that would
+    /// exceed it wait until they can be sent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `per_second` is zero.
+    #[must_use]
+    pub fn with_rate_limit(mut self, per_second: u32) -> Client {
+        assert!(
+            per_second > 0,
+            "rate limit must be at least one request per second"
+        );
+
+        self.rate_limiter = Some(Arc::new(rate_limit::RateLimiter::new(per_second)));
+        self
+    }
+
+    /// Masquerades as `srn` whenever a method that takes an optional
+    /// `masquerade` is passed `None`. Passing `Some` still overrides it for
+    /// that call. Methods that always masquerade as the entity they act on
+    /// (e.g. [`Client::get_account`] or [`Client::get_user`]) are unaffected.
+    #[must_use]
+    pub fn with_default_masquerade(mut self, srn: SystemResourceName) -> Client {
+        self.default_masquerade = Some(srn);
+        self
+    }
+
+    /// Asks Wyre for error messages in `language` (e.g. `es`) by sending it
+    /// as the `Accept-Language` header. The language Wyre actually used is in
+    /// [`ApiError::language`].
+    #[must_use]
+    pub fn with_language(mut self, language: &str) -> Client {
+        self.language = Some(language.to_owned());
+        self
+    }
+
+    /// Adds the query parameter `name=value` to every request, for
+    /// parameters Wyre supports that this crate doesn't know about yet. Can
+    /// be called more than once. To pass it to a single call, use it on a
+    /// clone of the client.
+    #[must_use]
+    pub fn with_extra_query(mut self, name: &str, value: &str) -> Client {
+        self.extra_query.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
     /// See [Get Master Account](https://docs.sendwyre.com/docs/get-master-account).
     pub async fn get_master_account(&self) -> Result<MasterAccount, Error> {
-        let url = format!("{}/v2/account", self.environment.api_url());
+        let url = format!("{}/account", self.environment.api_v2_url());
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+        let request = RequestBuilder::get(&url);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        self.send(request).await
+    }
+
+    /// Checks the credentials and connectivity without side effects by
+    /// fetching the master account. Rejected credentials are reported as
+    /// [`Error::Unauthorized`].
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.get_master_account().await.map(|_| ())
     }
 
     /// See [Create Account](https://docs.sendwyre.com/docs/create-account)
     pub async fn create_account(&self, body: CreateAccount) -> Result<Account, Error> {
-        let url = format!("{}/v3/accounts", self.environment.api_url());
+        body.validate()?;
 
-        let response = self
-            .http_client
-            .post(&url)
-            .bearer_auth(self.api_secret.expose_secret())
-            .json(&body)
-            .send()
-            .await?;
+        let url = format!("{}/accounts", self.environment.api_v3_url());
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        let request = RequestBuilder::post(&url).json(&body);
+
+        self.send(request).await
     }
 
     /// See [Get Account](https://docs.sendwyre.com/docs/get-account).
-    pub async fn get_account(&self, account_id: String) -> Result<Account, Error> {
-        let url = format!("{}/v3/accounts/{}", self.environment.api_url(), account_id);
+    pub async fn get_account(&self, account_id: AccountId) -> Result<Account, Error> {
+        let url = format!("{}/accounts/{}", self.environment.api_v3_url(), account_id);
 
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&[("masqueradeAs", account_id)])
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+        let request = RequestBuilder::get(&url).query(&[("masqueradeAs", account_id.to_string())]);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        self.send(request).await
     }
 
     /// See [Update Account](https://docs.sendwyre.com/docs/submit-account-info).
     pub async fn update_account(
         &self,
-        account_id: String,
+        account_id: AccountId,
         update: UpdateAccount,
     ) -> Result<Account, Error> {
-        let url = format!("{}/v3/accounts/{}", self.environment.api_url(), account_id);
+        let url = format!("{}/accounts/{}", self.environment.api_v3_url(), account_id);
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&[("masqueradeAs", account_id)])
-            .bearer_auth(self.api_secret.expose_secret())
-            .json(&update)
-            .send()
-            .await?;
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", account_id.to_string())])
+            .json(&update);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
+        self.send(request).await
+    }
+
+    /// Applies `update` with [`Client::update_account`], then polls
+    /// [`Client::get_account`] until Wyre has finished reviewing it, i.e. the
+    /// account is no longer `PENDING`. The first wait is one second and it
+    /// doubles up to 30 seconds between attempts.
+    ///
+    /// Returns the reviewed account along with the fields that were sent back
+    /// for correction and their notes (see [`Account::rejected_fields`]), or
+    /// [`Error::Timeout`] with the last account that was seen if `timeout`
+    /// elapses first.
+    ///
+    /// The interval is jittered slightly. Dropping the future stops polling,
+    /// nothing keeps running in the background.
+    pub async fn update_account_and_await(
+        &self,
+        account_id: AccountId,
+        update: UpdateAccount,
+        timeout: Duration,
+    ) -> Result<(Account, Vec<(ProfileFieldId, String)>), Error> {
+        const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+        let deadline = Instant::now() + timeout;
+        let mut poll_interval = Duration::from_secs(1);
+        let mut account = self.update_account(account_id.clone(), update).await?;
+
+        while account.status == AccountStatus::Pending {
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout {
+                    last_seen: Box::new(LastSeen::Account(account)),
+                });
+            }
+
+            poll::wait(poll_interval, deadline).await;
+            poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
+
+            account = self.get_account(account_id.clone()).await?;
         }
+
+        let rejected = account
+            .rejected_fields()
+            .into_iter()
+            .map(|(field_id, note)| (*field_id, note.to_owned()))
+            .collect();
+
+        Ok((account, rejected))
     }
 
     /// See [Upload Document](https://docs.sendwyre.com/docs/upload-document)
-    pub async fn upload_document<D: Into<ReqwestBody>>(
+    ///
+    /// The document is sent as the raw request body with its content type,
+    /// see [`Client::upload_document_multipart`] for endpoints that expect a
+    /// form instead.
+    pub async fn upload_document<D: Into<Vec<u8>>>(
         &self,
-        account_id: String,
+        account_id: AccountId,
         document: UploadDocument<D>,
     ) -> Result<Account, Error> {
-        let url = format!(
-            "{}/v3/accounts/{}/{}",
-            self.environment.api_url(),
-            account_id,
-            document.field_id
-        );
+        document.validate()?;
 
-        #[derive(Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct UploadDocumentQueryParams {
-            #[serde(skip_serializing_if = "Option::is_none")]
-            document_type: Option<DocumentType>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            document_sub_type: Option<DocumentSubType>,
-            masquerade_as: String,
-        }
+        let request = self
+            .upload_document_request(&account_id, &document)
+            .header(CONTENT_TYPE, document.content_type)
+            .body(document.document.into());
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&UploadDocumentQueryParams {
-                document_type: document.document_type,
-                document_sub_type: document.document_sub_type,
-                masquerade_as: account_id,
-            })
-            .bearer_auth(self.api_secret.expose_secret())
-            .header(reqwest::header::CONTENT_TYPE, document.content_type)
-            .body(document.document)
-            .send()
-            .await?;
+        self.send(request).await
+    }
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
+    /// Like [`Client::upload_document`], but sends the document as the `file`
+    /// field of a `multipart/form-data` body.
+    pub async fn upload_document_multipart<D: Into<Vec<u8>>>(
+        &self,
+        account_id: AccountId,
+        document: UploadDocument<D>,
+    ) -> Result<Account, Error> {
+        document.validate()?;
+
+        let request = self
+            .upload_document_request(&account_id, &document)
+            .multipart_file(
+                "file",
+                "document",
+                &document.content_type,
+                &document.document.into(),
+            );
+
+        self.send(request).await
+    }
+
+    /// Uploads the `front` and then the `back` of a government id with
+    /// [`Client::upload_document`] and returns the account after the second
+    /// upload. Fails with [`Error::Validation`] if `document_type` only has
+    /// one side (e.g. `PASSPORT`), upload those directly instead.
+    pub async fn upload_government_id<D: Into<Vec<u8>>>(
+        &self,
+        account_id: AccountId,
+        document_type: DocumentType,
+        front: D,
+        back: D,
+        content_type: &str,
+    ) -> Result<Account, Error> {
+        if !document_type.requires_both_sides() {
+            return Err(ValidationError(format!(
+                "{:?} documents only have one side, upload them with upload_document",
+                document_type
+            ))
+            .into());
         }
+
+        let side = |document_sub_type, document| UploadDocument {
+            field_id: ProfileFieldId::IndividualGovernmentId,
+            document_type: Some(document_type.clone()),
+            document_sub_type: Some(document_sub_type),
+            document,
+            content_type: content_type.to_owned(),
+        };
+
+        self.upload_document(account_id.clone(), side(DocumentSubType::Front, front))
+            .await?;
+        self.upload_document(account_id, side(DocumentSubType::Back, back))
+            .await
     }
 
     /// See [ACH - Create Payment Method](https://docs.sendwyre.com/docs/ach-create-payment-method-processor-token-model).
     pub async fn create_ach_payment_method(
         &self,
         body: CreateAchPaymentMethod,
-        masquerade: Option<String>,
+        masquerade: Option<SystemResourceName>,
     ) -> Result<PaymentMethod, Error> {
-        let url = format!("{}/v2/paymentMethods", self.environment.api_url());
+        let url = format!("{}/paymentMethods", self.environment.api_v2_url());
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&[("masqueradeAs", masquerade.unwrap_or_default())])
-            .bearer_auth(self.api_secret.expose_secret())
-            .json(&body)
-            .send()
-            .await?;
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))])
+            .json(&body);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        self.send(request).await
+    }
+
+    /// See [Create Payment Method](https://docs.sendwyre.com/docs/create-payment-method).
+    pub async fn create_payment_method(
+        &self,
+        body: CreatePaymentMethod,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<PaymentMethod, Error> {
+        let url = format!("{}/paymentMethods", self.environment.api_v2_url());
+
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))])
+            .json(&body);
+
+        self.send(request).await
     }
 
     /// See [List Payment Methods](https://docs.sendwyre.com/docs/list-payment-methods).
     pub async fn get_payment_methods(
         &self,
-        masquerade: Option<String>,
+        masquerade: Option<SystemResourceName>,
         offset: usize,
         limit: usize,
     ) -> Result<PaymentMethodList, Error> {
-        let url = format!("{}/v2/paymentMethods", self.environment.api_url());
-
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&[
-                ("offset", offset.to_string()),
-                ("limit", limit.to_string()),
-                ("masqueradeAs", masquerade.unwrap_or_default()),
-            ])
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+        let url = format!("{}/paymentMethods", self.environment.api_v2_url());
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
+        let request = RequestBuilder::get(&url)
+            .query(&[("offset", offset), ("limit", limit)])
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))]);
+
+        self.send(request).await
+    }
+
+    /// See [Get Payment Method](https://docs.sendwyre.com/reference/get-payment-method).
+    pub async fn get_payment_method(
+        &self,
+        payment_method_id: PaymentMethodId,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<PaymentMethod, Error> {
+        let url = format!(
+            "{}/paymentMethod/{}",
+            self.environment.api_v2_url(),
+            payment_method_id
+        );
+
+        let request =
+            RequestBuilder::get(&url).query(&[("masqueradeAs", self.masquerade_param(masquerade))]);
+
+        self.send(request).await
+    }
+
+    /// Polls [`Client::get_payment_method`] until the payment method is
+    /// `ACTIVE`, waiting one second after the first attempt and doubling the
+    /// wait up to 30 seconds between attempts.
+    ///
+    /// Returns [`Error::PaymentMethodRejected`] if Wyre rejects the payment
+    /// method, or [`Error::Timeout`] with the last payment method that was
+    /// seen if `timeout` elapses first.
+    ///
+    /// The interval is jittered slightly. Dropping the future stops polling,
+    /// nothing keeps running in the background.
+    pub async fn await_payment_method_active(
+        &self,
+        payment_method_id: PaymentMethodId,
+        masquerade: Option<SystemResourceName>,
+        timeout: Duration,
+    ) -> Result<PaymentMethod, Error> {
+        const MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+        let deadline = Instant::now() + timeout;
+        let mut poll_interval = Duration::from_secs(1);
+
+        loop {
+            let payment_method = self
+                .get_payment_method(payment_method_id.clone(), masquerade.clone())
+                .await?;
+
+            match payment_method.status {
+                PaymentMethodStatus::Active => return Ok(payment_method),
+                PaymentMethodStatus::Rejected => {
+                    return Err(Error::PaymentMethodRejected(Box::new(payment_method)))
+                }
+                _ => {}
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout {
+                    last_seen: Box::new(LastSeen::PaymentMethod(payment_method)),
+                });
+            }
+
+            poll::wait(poll_interval, deadline).await;
+            poll_interval = (poll_interval * 2).min(MAX_POLL_INTERVAL);
         }
     }
 
@@ -250,164 +603,627 @@ impl Client {
     pub async fn create_transfer(
         &self,
         body: CreateTransfer,
-        masquerade: Option<String>,
+        masquerade: Option<SystemResourceName>,
     ) -> Result<Transfer, Error> {
-        let url = format!("{}/v3/transfers", self.environment.api_url());
+        body.validate()?;
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&[("masqueradeAs", masquerade.unwrap_or_default())])
-            .bearer_auth(self.api_secret.expose_secret())
-            .json(&body)
-            .send()
-            .await?;
+        let url = format!("{}/transfers", self.environment.api_v3_url());
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))])
+            .json(&body);
+
+        self.send(request).await
     }
 
     /// See [Get Transfer](https://docs.sendwyre.com/docs/get-transfer).
     pub async fn get_transfer(
         &self,
-        transfer_id: String,
-        masquerade: Option<String>,
+        transfer_id: TransferId,
+        masquerade: Option<SystemResourceName>,
     ) -> Result<Transfer, Error> {
         let url = format!(
-            "{}/v3/transfers/{}",
-            self.environment.api_url(),
+            "{}/transfers/{}",
+            self.environment.api_v3_url(),
             transfer_id
         );
 
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&[("masqueradeAs", masquerade.unwrap_or_default())])
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+        let request =
+            RequestBuilder::get(&url).query(&[("masqueradeAs", self.masquerade_param(masquerade))]);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        self.send(request).await
     }
 
-    /// See [Create User](https://docs.sendwyre.com/reference/create-user).
-    pub async fn create_user(&self, req: ModifyUser) -> Result<User, Error> {
-        let url = format!("{}/v3/users", self.environment.api_url());
+    /// See [Transfer History](https://docs.sendwyre.com/reference/transfer-history).
+    pub async fn list_transfers(
+        &self,
+        masquerade: Option<SystemResourceName>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<TransferList, Error> {
+        let url = format!("{}/transfers", self.environment.api_v3_url());
 
-        let response = self
-            .http_client
-            .post(&url)
-            .json(&req)
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+        let request = RequestBuilder::get(&url)
+            .query(&[("offset", offset), ("limit", limit)])
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))]);
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
-        }
+        self.send(request).await
     }
 
-    /// See [Get User](https://docs.sendwyre.com/reference/get-user)
-    pub async fn get_user(&self, user_id: String, scope: UserScope) -> Result<User, Error> {
-        let url = format!("{}/v3/users/{}", self.environment.api_url(), user_id);
-
-        let response = self
-            .http_client
-            .get(&url)
-            .query(&[
-                ("masqueradeAs", format!("user:{}", user_id)),
-                ("scopes", scope.to_string()),
-            ])
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+    /// Confirms an `UNCONFIRMED` transfer so it's executed. See [Confirm Transfer](https://docs.sendwyre.com/reference/confirm-transfer).
+    pub async fn confirm_transfer(
+        &self,
+        transfer_id: TransferId,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<Transfer, Error> {
+        let url = format!(
+            "{}/transfers/{}/confirm",
+            self.environment.api_v3_url(),
+            transfer_id
+        );
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", self.masquerade_param(masquerade))]);
+
+        self.send(request).await
+    }
+
+    /// Finds the transfer created with `custom_id` by walking the pages of
+    /// [`Client::list_transfers`], or returns `None` if there is none.
+    ///
+    /// Wyre doesn't allow a transfer to be changed once it's created, so a
+    /// `custom_id` can't be attached afterwards. Set it in
+    /// [`CreateTransfer::custom_id`] to correlate a transfer with an order,
+    /// transfers created without one have to be matched on their other
+    /// fields instead.
+    pub async fn get_transfer_by_custom_id(
+        &self,
+        custom_id: &str,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<Option<Transfer>, Error> {
+        let pages = self.transfer_pages(masquerade);
+        futures03::pin_mut!(pages);
+
+        while let Some(page) = pages.next().await {
+            if let Some(transfer) = page?
+                .into_iter()
+                .find(|transfer| transfer.custom_id.as_deref() == Some(custom_id))
+            {
+                return Ok(Some(transfer));
+            }
         }
+
+        Ok(None)
     }
 
-    /// See [Update User](https://docs.sendwyre.com/reference/upload-user-data)
-    pub async fn update_user(&self, user_id: String, req: ModifyUser) -> Result<User, Error> {
-        let url = format!("{}/v3/users/{}", self.environment.api_url(), user_id);
+    /// Confirms every `UNCONFIRMED` transfer, e.g. ones left behind after a
+    /// crash between creating and confirming them.
+    ///
+    /// The pages of [`Client::list_transfers`] are read in turn, and the
+    /// unconfirmed transfers on each page are confirmed before the next page
+    /// is fetched, since they expire quickly. Transfers that have already
+    /// expired are skipped. A failure to confirm one transfer doesn't stop
+    /// the others, the result of each is returned alongside its id.
+    pub async fn confirm_unconfirmed_transfers(
+        &self,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<Vec<(TransferId, Result<Transfer, Error>)>, Error> {
+        let pages = self.transfer_pages(masquerade.clone());
+        futures03::pin_mut!(pages);
+
+        let mut results = Vec::new();
+        while let Some(page) = pages.next().await {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64);
+            for transfer in page? {
+                let expired = matches!(transfer.expires_at, Some(expires_at) if expires_at <= now);
+                if transfer.status != TransferStatus::Unconfirmed || expired {
+                    continue;
+                }
 
-        let response = self
-            .http_client
-            .post(&url)
-            .query(&[("masqueradeAs", format!("user:{}", user_id))])
-            .json(&req)
-            .bearer_auth(self.api_secret.expose_secret())
-            .send()
-            .await?;
+                let result = self
+                    .confirm_transfer(transfer.id.clone(), masquerade.clone())
+                    .await;
+                results.push((transfer.id, result));
+            }
+        }
 
-        let status = response.status();
-        match status {
-            StatusCode::OK => Ok(response.json().await?),
-            _ => Err(Error::Api(response.json().await?)),
+        Ok(results)
+    }
+
+    /// Like [`Client::get_transfer`], for a transfer SRN such as the
+    /// `trigger` of a webhook callback (see [`MetadataCallbackPayload`]).
+    /// Any other kind of SRN is rejected with [`Error::Validation`].
+    pub async fn get_transfer_srn(
+        &self,
+        srn: SystemResourceName,
+        masquerade: Option<SystemResourceName>,
+    ) -> Result<Transfer, Error> {
+        match srn {
+            SystemResourceName::Transfer(id) => self.get_transfer(id.into(), masquerade).await,
+            srn => Err(ValidationError(format!("not a transfer srn: {}", srn)).into()),
         }
     }
-}
 
-/// Error received from [Client::from_env]
-pub enum ClientFromEnvironmentError {
-    /// No `WYRE_API_KEY` variable
-    MissingApiKey,
+    /// Writes every transfer to `writer` as newline-delimited JSON, one page
+    /// of [`Client::list_transfers`] at a time so memory use doesn't grow
+    /// with the transfer history. Returns the number of transfers written.
+    pub async fn stream_transfers_ndjson<W: Write>(
+        &self,
+        masquerade: Option<SystemResourceName>,
+        mut writer: W,
+    ) -> Result<usize, Error> {
+        let pages = self.transfer_pages(masquerade);
+        futures03::pin_mut!(pages);
+
+        let mut count = 0;
+        while let Some(page) = pages.next().await {
+            for transfer in page? {
+                serde_json::to_writer(&mut writer, &transfer).map_err(std::io::Error::from)?;
+                writer.write_all(b"\n")?;
+                count += 1;
+            }
+        }
 
-    /// No `WYRE_API_SECRET` variable
-    MissingApiSecret,
+        writer.flush()?;
+        Ok(count)
+    }
 
-    /// No `WYRE_ENVIRONMENT` variable
-    MissingEnvironment,
+    /// The pages of [`Client::list_transfers`], fetched one at a time as the
+    /// stream is polled. Ends after the first error.
+    fn transfer_pages(
+        &self,
+        masquerade: Option<SystemResourceName>,
+    ) -> impl Stream<Item = Result<Vec<Transfer>, Error>> + '_ {
+        const PAGE_SIZE: usize = 100;
+
+        stream::unfold(Some(0), move |offset| {
+            let masquerade = masquerade.clone();
+            async move {
+                let offset = offset?;
+                match self.list_transfers(masquerade, offset, PAGE_SIZE).await {
+                    Ok(page) => {
+                        let count = page.data.len();
+                        let next = offset + count;
+                        let next = if count == 0 || next >= page.records_total {
+                            None
+                        } else {
+                            Some(next)
+                        };
+                        Some((Ok(page.data), next))
+                    }
+                    Err(error) => Some((Err(error), None)),
+                }
+            }
+        })
+    }
 
-    /// The `WYRE_ENVIRONMENT` variable didn't match an expected value.
-    EnvironmentParseError(EnvironmentParseError),
-}
+    /// Polls [`Client::get_transfer`] every `poll_interval` until the transfer
+    /// reaches a terminal status (`COMPLETED`, `FAILED`, `REVERSED` or
+    /// `EXPIRED`). If `timeout` elapses first, [`Error::Timeout`] is returned
+    /// with the last transfer that was seen.
+    ///
+    /// The interval is jittered slightly. Dropping the future stops polling,
+    /// nothing keeps running in the background.
+    pub async fn await_transfer_terminal(
+        &self,
+        transfer_id: TransferId,
+        masquerade: Option<SystemResourceName>,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Transfer, Error> {
+        let deadline = Instant::now() + timeout;
 
-impl From<EnvironmentParseError> for ClientFromEnvironmentError {
-    fn from(error: EnvironmentParseError) -> Self {
-        ClientFromEnvironmentError::EnvironmentParseError(error)
+        loop {
+            let transfer = self
+                .get_transfer(transfer_id.clone(), masquerade.clone())
+                .await?;
+
+            if transfer.status.is_terminal() {
+                return Ok(transfer);
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout {
+                    last_seen: Box::new(LastSeen::Transfer(transfer)),
+                });
+            }
+
+            poll::wait(poll_interval, deadline).await;
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, convert::TryFrom};
+    /// See [Create User](https://docs.sendwyre.com/reference/create-user).
+    pub async fn create_user(&self, req: ModifyUser) -> Result<User, Error> {
+        let url = format!("{}/users", self.environment.api_v3_url());
 
-    use bigdecimal::BigDecimal;
-    use futures03::{FutureExt, TryFutureExt};
-    use tokio01::runtime::Runtime as Runtime01;
-    use tokio10::runtime::Runtime as Runtime10;
+        let request = RequestBuilder::post(&url).json(&req);
 
-    use crate::{
-        self as wyre, Address, ModifyUser, UserFieldId, UserFieldStatus, UserFieldType, UserScope,
-        UserStatus,
-    };
+        self.send(request).await
+    }
 
-    fn client_from_env() -> wyre::Client {
-        use std::env;
-        dotenv::dotenv().unwrap();
+    /// Creates `users` with [`Client::create_user`], sending at most
+    /// `concurrency` (at least one) requests at a time. Returns the result for
+    /// each user in the order they were given, so the failures can be
+    /// retried on their own.
+    ///
+    /// Requests still go through the client's rate limit, if any. Failed
+    /// requests aren't retried since that could create duplicate users.
+    pub async fn create_users(
+        &self,
+        users: Vec<ModifyUser>,
+        concurrency: usize,
+    ) -> Vec<Result<User, Error>> {
+        stream::iter(users)
+            .map(|user| self.create_user(user))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
 
-        wyre::Client::new(
-            env::var("WYRE_API_KEY").unwrap().into(),
-            env::var("WYRE_API_SECRET").unwrap().into(),
-            wyre::Environment::Test,
-        )
+    /// See [Get User](https://docs.sendwyre.com/reference/get-user)
+    ///
+    /// Multiple `scopes` are sent comma separated, the `scopes` query
+    /// parameter is omitted when none are given.
+    pub async fn get_user(&self, user_id: UserId, scopes: &[UserScope]) -> Result<User, Error> {
+        let url = format!("{}/users/{}", self.environment.api_v3_url(), user_id);
+
+        let mut request =
+            RequestBuilder::get(&url).query(&[("masqueradeAs", format!("user:{}", user_id))]);
+
+        if !scopes.is_empty() {
+            let scopes = scopes
+                .iter()
+                .map(UserScope::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            request = request.query(&[("scopes", scopes)]);
+        }
+
+        self.send(request).await
     }
 
-    fn all_fields() -> HashMap<UserFieldId, UserFieldType> {
-        let fields = vec![
-            (
+    /// See [Update User](https://docs.sendwyre.com/reference/upload-user-data)
+    pub async fn update_user(&self, user_id: UserId, req: ModifyUser) -> Result<User, Error> {
+        let url = format!("{}/users/{}", self.environment.api_v3_url(), user_id);
+
+        let request = RequestBuilder::post(&url)
+            .query(&[("masqueradeAs", format!("user:{}", user_id))])
+            .json(&req);
+
+        self.send(request).await
+    }
+
+    /// Sums the total balances of every account, user and wallet in `owners`
+    /// per currency. A few balances are fetched at a time (within the
+    /// [rate limit](Client::with_rate_limit), if one is set), any other kind
+    /// of SRN is rejected with [`Error::Validation`].
+    ///
+    /// A balance in a currency this crate doesn't know about is also rejected
+    /// with [`Error::Validation`], since it can't be told apart from other
+    /// unknown currencies and summing them would mix them up.
+    pub async fn aggregate_balances(
+        &self,
+        owners: &[SystemResourceName],
+    ) -> Result<HashMap<Currency, Amount>, Error> {
+        let mut balances = stream::iter(owners)
+            .map(|owner| self.total_balances(owner))
+            .buffer_unordered(AGGREGATE_BALANCES_CONCURRENCY);
+
+        let mut total = HashMap::new();
+        while let Some(balances) = balances.next().await {
+            for (currency, amount) in balances? {
+                if currency == Currency::Other {
+                    return Err(ValidationError(format!(
+                        "a balance of {} is in an unknown currency",
+                        amount
+                    ))
+                    .into());
+                }
+
+                *total.entry(currency).or_insert_with(Amount::default) += amount;
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Reserves a widget order and returns the url to send the user to for
+    /// hosted onboarding. See [Wallet Order Reservations](https://docs.sendwyre.com/reference/walletorderreservations)
+    pub async fn create_onboarding_url(
+        &self,
+        body: CreateOnboardingUrl,
+    ) -> Result<OnboardingUrl, Error> {
+        let url = format!("{}/orders/reserve", self.environment.api_v3_url());
+
+        let request = RequestBuilder::post(&url).json(&body);
+
+        self.send(request).await
+    }
+}
+
+impl Client {
+    /// The total balances of an account, user or wallet.
+    async fn total_balances(
+        &self,
+        owner: &SystemResourceName,
+    ) -> Result<HashMap<Currency, Amount>, Error> {
+        match owner {
+            SystemResourceName::Account(id) => {
+                Ok(self.get_account(id.as_str().into()).await?.total_balances)
+            }
+            SystemResourceName::User(id) => {
+                Ok(self.get_user(id.as_str().into(), &[]).await?.total_balances)
+            }
+            SystemResourceName::Wallet(id) => {
+                let url = format!("{}/wallet/{}", self.environment.api_v2_url(), id);
+
+                #[derive(Deserialize)]
+                struct WalletResponse {
+                    balances: HashMap<Currency, Amount>,
+                }
+
+                let request = RequestBuilder::get(&url);
+
+                let response: WalletResponse = self.send(request).await?;
+                Ok(response.balances)
+            }
+            owner => {
+                Err(ValidationError(format!("balances can't be fetched for {}", owner)).into())
+            }
+        }
+    }
+
+    /// The `masqueradeAs` query value for an optional per-call masquerade,
+    /// falling back to the default masquerade.
+    fn masquerade_param(&self, masquerade: Option<SystemResourceName>) -> Option<String> {
+        masquerade
+            .or_else(|| self.default_masquerade.clone())
+            .map(|srn| srn.to_string())
+    }
+
+    /// The url and query shared by both ways of uploading a document.
+    fn upload_document_request<D>(
+        &self,
+        account_id: &AccountId,
+        document: &UploadDocument<D>,
+    ) -> RequestBuilder {
+        let url = format!(
+            "{}/accounts/{}/{}",
+            self.environment.api_v3_url(),
+            account_id,
+            document.field_id
+        );
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct UploadDocumentQueryParams {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            document_type: Option<DocumentType>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            document_sub_type: Option<DocumentSubType>,
+            masquerade_as: String,
+        }
+
+        RequestBuilder::post(&url).query(&UploadDocumentQueryParams {
+            document_type: document.document_type.clone(),
+            document_sub_type: document.document_sub_type.clone(),
+            masquerade_as: account_id.to_string(),
+        })
+    }
+
+    /// Replaces the `reqwest` client with one built from the current
+    /// configuration.
+    #[cfg(feature = "reqwest")]
+    fn rebuild_http_client(&mut self) -> Result<(), reqwest::Error> {
+        let mut builder =
+            ReqwestClient::builder().tls_built_in_root_certs(self.built_in_root_certificates);
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        for proxy in &self.proxies {
+            builder = builder.proxy(proxy.clone());
+        }
+
+        self.http_client = builder.build()?;
+        Ok(())
+    }
+
+    /// Sends an authenticated request and parses the response as `T`, or as
+    /// an [`ApiError`] for any status other than `200 OK` (or
+    /// [`Error::Http`] if it isn't one). An empty `200 OK`
+    /// body is accepted when `T` is `()`.
+    async fn send<T: DeserializeOwned>(&self, mut request: RequestBuilder) -> Result<T, Error> {
+        if !self.extra_query.is_empty() {
+            request = request.query(&self.extra_query);
+        }
+        if let Some(language) = &self.language {
+            request = request.header(ACCEPT_LANGUAGE, language);
+        }
+
+        let request = request
+            .bearer_auth(self.api_secret.expose_secret())
+            .header(API_VERSION_HEADER, &self.api_version)
+            .build()?;
+
+        if self.request_logging {
+            let description = DryRunRequest::new(&request);
+            log::debug!(
+                "{} {} {}",
+                description.method,
+                description.url,
+                description.body.as_deref().unwrap_or_default()
+            );
+        }
+
+        if let Some(dry_run) = &self.dry_run {
+            (dry_run.0)(&DryRunRequest::new(&request));
+            return Err(Error::DryRun);
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+
+        let response = match &self.transport {
+            Some(transport) => transport.execute(request).await?,
+            #[cfg(feature = "reqwest")]
+            None => HttpTransport::execute(&self.http_client, request).await?,
+            #[cfg(not(feature = "reqwest"))]
+            None => {
+                return Err(Error::Backend(
+                    "no transport is set, see Client::with_transport".into(),
+                ))
+            }
+        };
+
+        let request_id = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        let status = response.status();
+        let body = response.into_body();
+        match status {
+            // Some endpoints respond with an empty body, read it as `null`.
+            StatusCode::OK if body.is_empty() => Ok(serde_json::from_slice(b"null")?),
+            StatusCode::OK => Ok(serde_json::from_slice(&body)?),
+            _ => {
+                // Gateways and proxies may answer with e.g. an HTML page.
+                let mut error: ApiError = match serde_json::from_slice(&body) {
+                    Ok(error) => error,
+                    Err(_) => {
+                        return Err(Error::Http {
+                            status,
+                            body: String::from_utf8_lossy(&body).into_owned(),
+                            request_id,
+                        })
+                    }
+                };
+                error.request_id = request_id;
+
+                match Error::from(error) {
+                    Error::Api(error)
+                        if status == StatusCode::UNAUTHORIZED
+                            || status == StatusCode::FORBIDDEN =>
+                    {
+                        Err(Error::Unauthorized(error))
+                    }
+                    error => Err(error),
+                }
+            }
+        }
+    }
+}
+
+/// Error received from [Client::from_env]
+pub enum ClientFromEnvironmentError {
+    /// No `WYRE_API_KEY` variable
+    MissingApiKey,
+
+    /// No `WYRE_API_SECRET` variable
+    MissingApiSecret,
+
+    /// No `WYRE_ENVIRONMENT` variable
+    MissingEnvironment,
+
+    /// The `WYRE_ENVIRONMENT` variable didn't match an expected value.
+    EnvironmentParseError(EnvironmentParseError),
+
+    /// The dotenv file passed to `Client::from_env_file` couldn't be loaded.
+    #[cfg(feature = "dotenv")]
+    EnvFile(dotenv::Error),
+}
+
+impl From<EnvironmentParseError> for ClientFromEnvironmentError {
+    fn from(error: EnvironmentParseError) -> Self {
+        ClientFromEnvironmentError::EnvironmentParseError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bigdecimal::BigDecimal;
+    use futures03::{FutureExt, TryFutureExt};
+    use tokio01::runtime::Runtime as Runtime01;
+    use tokio10::runtime::Runtime as Runtime10;
+
+    use crate::{
+        self as wyre, Address, ModifyUser, UserFieldId, UserFieldStatus, UserFieldType, UserScope,
+        UserStatus,
+    };
+
+    fn client_from_env() -> wyre::Client {
+        use std::env;
+        dotenv::dotenv().unwrap();
+
+        wyre::Client::new(
+            env::var("WYRE_API_KEY").unwrap().into(),
+            env::var("WYRE_API_SECRET").unwrap().into(),
+            wyre::Environment::Test,
+        )
+    }
+
+    /// Responds to every request with `status` and `body`, recording the
+    /// requests it receives.
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        status: u16,
+        body: &'static str,
+        headers: Vec<(&'static str, &'static str)>,
+        /// Overrides `status` and `body` for the first requests, in order.
+        responses: Vec<(u16, &'static str)>,
+        requests: std::sync::Mutex<Vec<http::Request<Vec<u8>>>>,
+    }
+
+    impl wyre::HttpTransport for std::sync::Arc<MockTransport> {
+        fn execute(
+            &self,
+            request: http::Request<Vec<u8>>,
+        ) -> futures03::future::BoxFuture<'_, Result<http::Response<Vec<u8>>, wyre::Error>>
+        {
+            let (status, body) = {
+                let mut requests = self.requests.lock().unwrap();
+                requests.push(request);
+                self.responses
+                    .get(requests.len() - 1)
+                    .copied()
+                    .unwrap_or((self.status, self.body))
+            };
+
+            let mut response = http::Response::builder().status(status);
+            for (name, value) in &self.headers {
+                response = response.header(*name, *value);
+            }
+            let response = response.body(body.as_bytes().to_vec()).unwrap();
+
+            Box::pin(futures03::future::ready(Ok(response)))
+        }
+    }
+
+    fn mock_client(
+        status: u16,
+        body: &'static str,
+    ) -> (wyre::Client, std::sync::Arc<MockTransport>) {
+        let transport = std::sync::Arc::new(MockTransport {
+            status,
+            body,
+            ..Default::default()
+        });
+
+        let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+            .with_transport(transport.clone());
+
+        (client, transport)
+    }
+
+    fn all_fields() -> HashMap<UserFieldId, UserFieldType> {
+        let fields = vec![
+            (
                 UserFieldId::FirstName,
                 UserFieldType::String(Some("John".to_owned())),
             ),
@@ -443,6 +1259,31 @@ mod tests {
         fields.into_iter().collect()
     }
 
+    #[test]
+    fn modify_user_builder() {
+        let mod_user = ModifyUser::builder()
+            .first_name("John")
+            .last_name("Smith")
+            .residence_address(Address {
+                street1: Some("1234 Sesame Street".to_owned()),
+                street2: Some("Apt 34".to_owned()),
+                city: Some("Hollywood".to_owned()),
+                state: Some("CA".to_owned()),
+                postal_code: Some("90210".to_owned()),
+                country: Some("US".to_owned()),
+            })
+            .date_of_birth("1990-03-02")
+            .email("email@website.com")
+            .scope(UserScope::Transfer)
+            .blockchain(wyre::Blockchain::Eth)
+            .build();
+
+        assert_eq!(mod_user.fields, all_fields());
+        assert_eq!(mod_user.scopes, vec![UserScope::Transfer]);
+        assert_eq!(mod_user.blockchains, vec![wyre::Blockchain::Eth]);
+        assert!(!mod_user.immediate);
+    }
+
     #[test]
     fn create_user_all_fields() {
         let mod_user = ModifyUser {
@@ -559,12 +1400,12 @@ mod tests {
             .unwrap();
 
         let gotten_user = runtime
-            .block_on(client.get_user(initial_user.id.clone(), scope))
+            .block_on(client.get_user(initial_user.id.clone(), &[scope]))
             .unwrap();
 
         // The status may occasionally be "Pending" depending on how quickly the initial User was returned,
         // but we're going to ignore that since it's a small technicality
-        initial_user.status = gotten_user.status;
+        initial_user.status = gotten_user.status.clone();
 
         assert_eq!(initial_user, gotten_user);
     }
@@ -617,145 +1458,2436 @@ mod tests {
         .unwrap();
     }
 
+    /// An individual account with a mix of open, pending and rejected
+    /// profile fields.
+    fn account_fixture() -> wyre::Account {
+        serde_json::from_str(
+            r#"
+            {
+                "id": "AC_YBA9C8WBAG3",
+                "status": "OPEN",
+                "type": "INDIVIDUAL",
+                "country": "US",
+                "createdAt": 1541789972000,
+                "updatedAt": 1541789972000,
+                "depositAddresses": {
+                  "ETH": "0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                  "BTC": "1HPsCB9n9jdBxN6D9nDxX9YZ8v7MnchwPq"
+                },
+                "totalBalances": {
+                  "BTC": 0,
+                  "ETH": 0
+                },
+                "availableBalances": {
+                  "BTC": 0,
+                  "ETH": 0
+                },
+                "profileFields": [
+                  {
+                    "fieldId": "individualCellphoneNumber",
+                    "fieldType": "CELLPHONE",
+                    "value": null,
+                    "note": null,
+                    "status": "OPEN"
+                  },
+                  {
+                    "fieldId": "individualEmail",
+                    "fieldType": "EMAIL",
+                    "value": "johnnyquest22@yolo.com",
+                    "note": null,
+                    "status": "PENDING"
+                  },
+                  {
+                    "fieldId": "individualLegalName",
+                    "fieldType": "STRING",
+                    "value": "Johnny Quest",
+                    "note": null,
+                    "status": "PENDING"
+                  },
+                  {
+                    "fieldId": "individualResidenceAddress",
+                    "fieldType": "ADDRESS",
+                    "value": {
+                      "street1": "1 Market St",
+                      "street2": "Suite 402",
+                      "city": "San Francisco",
+                      "state": "CA",
+                      "postalCode": "94105",
+                      "country": "US"
+                    },
+                    "note": null,
+                    "status": "PENDING"
+                  },
+                  {
+                    "fieldId": "individualGovernmentId",
+                    "fieldType": "DOCUMENT",
+                    "value": [],
+                    "note": null,
+                    "status": "OPEN"
+                  },
+                  {
+                    "fieldId": "individualSourceOfFunds",
+                    "fieldType": "PAYMENT_METHOD",
+                    "value": null,
+                    "note": "Payment method not yet submitted",
+                    "status": "OPEN"
+                  },
+                  {
+                    "fieldId": "individualSsn",
+                    "fieldType": "STRING",
+                    "value": null,
+                    "note": null,
+                    "status": "OPEN"
+                  }
+                ],
+                "documents": [
+                  {
+                    "id": "DO_2BMBT9JNQ6Q",
+                    "documentType": "GOVT_ID",
+                    "documentSubType": "FRONT",
+                    "status": "APPROVED",
+                    "uploadedAt": 1541789972000
+                  },
+                  {
+                    "id": "DO_8WYPCZ3KA4M",
+                    "documentType": "GOVT_ID",
+                    "documentSubType": "BACK",
+                    "status": "PENDING",
+                    "uploadedAt": 1541789973000
+                  }
+                ]
+              }
+            "#,
+        )
+        .unwrap()
+    }
+
     #[test]
-    fn can_create_ach_transfer_via_plaid() {
-        dotenv::dotenv().unwrap();
+    fn account_serde() {
+        use super::*;
+        let account = account_fixture();
 
-        let rt_10 = Runtime10::new().unwrap();
-        let plaid_client = plaid::Client::new(
-            std::env::var("PLAID_CLIENT_ID").unwrap(),
-            std::env::var("PLAID_SECRET").unwrap(),
-            plaid::client::Environment::Sandbox,
-        );
+        assert_eq!(account.status, AccountStatus::Open);
+        assert_eq!(account.profile_fields.len(), 7);
+    }
 
-        let public_token_response = rt_10
-            .block_on(plaid_client.create_sandbox_public_token("ins_1", &["auth", "identity"]))
-            .unwrap();
+    #[test]
+    fn account_documents() {
+        use super::*;
+        let account = account_fixture();
 
-        let exchange_token_response = rt_10
-            .block_on(plaid_client.exchange_public_token(&public_token_response.public_token))
-            .unwrap();
+        assert_eq!(account.documents[0].status, DocumentStatus::Approved);
+        assert_eq!(account.documents[1].status, DocumentStatus::Pending);
+    }
 
-        let accounts_response = rt_10
-            .block_on(plaid_client.get_accounts(&exchange_token_response.access_token, None))
-            .unwrap();
+    #[test]
+    fn account_rejected_fields() {
+        use super::*;
+        let account = account_fixture();
 
-        let processor_token_response = rt_10
-            .block_on(plaid_client.create_processor_token(
-                &exchange_token_response.access_token,
-                &accounts_response.accounts[0].account_id,
-                "wyre",
-            ))
-            .unwrap();
+        assert_eq!(
+            account.rejected_fields(),
+            vec![(
+                &ProfileFieldId::IndividualSourceOfFunds,
+                "Payment method not yet submitted"
+            )]
+        );
+    }
 
-        let mut rt_01 = Runtime01::new().unwrap();
-        let wyre_client = client_from_env();
-        let wyre_client: &'static _ = Box::leak(Box::new(wyre_client));
+    #[test]
+    fn account_diff_fields() {
+        use super::*;
+        let account = account_fixture();
+
+        let desired = vec![
+            CreateProfileField {
+                field_id: ProfileFieldId::IndividualLegalName,
+                value: ProfileFieldType::String(Some("Johnny Quest".to_owned())),
+            },
+            CreateProfileField {
+                field_id: ProfileFieldId::IndividualEmail,
+                value: ProfileFieldType::Email(Some("jquest@example.com".to_owned())),
+            },
+            CreateProfileField {
+                field_id: ProfileFieldId::IndividualDateOfBirth,
+                value: ProfileFieldType::Date(Some("1990-09-24".to_owned())),
+            },
+        ];
+        let changed: Vec<_> = account
+            .diff_fields(&desired)
+            .into_iter()
+            .map(|field| field.field_id)
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                ProfileFieldId::IndividualEmail,
+                ProfileFieldId::IndividualDateOfBirth
+            ]
+        );
+    }
 
-        let account = rt_01
-            .block_on(
-                wyre_client
-                    .create_account(wyre::CreateAccount {
-                        kind: wyre::AccountType::Individual,
-                        country: "US".into(),
-                        profile_fields: vec![
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualLegalName,
-                                value: wyre::ProfileFieldType::String(Some("Alice Loyd".into())),
-                            },
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualResidenceAddress,
-                                value: wyre::ProfileFieldType::Address(Some(wyre::Address {
-                                    street1: Some("7819 E. Stonybrook St.".into()),
-                                    street2: None,
-                                    city: Some("Seattle".into()),
-                                    state: Some("WA".into()),
-                                    postal_code: Some("98111".into()),
-                                    country: Some("US".into()),
-                                })),
-                            },
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualCellphoneNumber,
-                                value: wyre::ProfileFieldType::Cellphone(Some(
-                                    "+12062108021".into(),
-                                )),
-                            },
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualEmail,
-                                value: wyre::ProfileFieldType::Email(Some(
-                                    "test@example.com".into(),
-                                )),
-                            },
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualDateOfBirth,
-                                value: wyre::ProfileFieldType::Date(Some("1990-09-24".into())),
-                            },
-                            wyre::CreateProfileField {
-                                field_id: wyre::ProfileFieldId::IndividualSsn,
-                                value: wyre::ProfileFieldType::Date(Some("123-45-6789".into())),
-                            },
-                        ],
-                        referrer_account_id: None,
-                        subaccount: Some(true),
-                        disable_email: Some(true),
-                    })
-                    .boxed()
-                    .compat(),
-            )
-            .unwrap();
+    /// An approved business master account with identities and limits.
+    fn master_account_fixture() -> wyre::MasterAccount {
+        serde_json::from_str(
+            r#"
+            {
+                "id": "AC_XXXXXXXXXXX",
+                "srn": "account:AC_XXXXXXXXXXX",
+                "createdAt": 1583510914000,
+                "updatedAt": 1583510914000,
+                "deletedAt": null,
+                "disabledAt": null,
+                "lockedAt": null,
+                "lockedReason": null,
+                "underReviewAt": null,
+                "inReviewAt": null,
+                "complianceApprovedAt": 1583511014000,
+                "status": "APPROVED",
+                "stripeAccountId": null,
+                "profile": {
+                  "firstName": "",
+                  "lastName": "",
+                  "language": "en_US",
+                  "address": {
+                    "street1": "1 Market St",
+                    "street2": null,
+                    "city": "San Francisco",
+                    "state": "CA",
+                    "postalCode": "94105",
+                    "country": "US"
+                  },
+                  "businessAccount": true,
+                  "taxId": null,
+                  "doingBusinessAs": null,
+                  "website": null,
+                  "partnerLink": null,
+                  "ssn": null,
+                  "dateOfBirth": null,
+                  "notifyEmail": true,
+                  "notifyCellphone": true,
+                  "notifyApnsDevice": true,
+                  "onboardingDashboardCompleted": true,
+                  "displayCurrency": "USD",
+                  "cpfNumber": null,
+                  "type": "BUSINESS",
+                  "vertical": "WALLET",
+                  "ethereumVerificationAddress": null,
+                  "companyTitle": null,
+                  "partnerDisplayName": "Telcoin",
+                  "companyName": "Telcoin",
+                  "companyRegistrationNumber": null,
+                  "occupation": null,
+                  "purposeOfAccount": null,
+                  "country": "US",
+                  "name": "Telcoin"
+                },
+                "paymentMethods": [],
+                "identities": [
+                  {
+                    "srn": "email:test@sendwyre.com",
+                    "type": "EMAIL",
+                    "verified": true,
+                    "verifiedAt": 1497296436000,
+                    "createdAt": 1497296436000
+                  },
+                  {
+                    "srn": "cellphone:+15555555555",
+                    "type": "CELLPHONE",
+                    "verified": false,
+                    "verifiedAt": null
+                  }
+                ],
+                "depositAddresses": {
+                  "ETH": "0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                  "BTC": "1HPsCB9n9jdBxN6D9nDxX9YZ8v7MnchwPq"
+                },
+                "ledgers": [],
+                "documents": [],
+                "srnLimits": [
+                  {
+                    "srn": "account:AC_XXXXXXXXXXX",
+                    "period": "DAILY",
+                    "currency": "USD",
+                    "amount": 2500
+                  }
+                ],
+                "cellphone": null,
+                "pusherChannel": "e8a1d6ad3e0b3c7d2f4e6d9b1c0a5f8e",
+                "email": "ops@example.com",
+                "session": null,
+                "loginAt": 1583510914000,
+                "lastLoginIp": null,
+                "lastLoginLocation": null,
+                "loc": null,
+                "emailIdentity": null,
+                "totalBalances": {
+                  "BTC": 0.0031,
+                  "USD": 1250.5
+                },
+                "availableBalances": {
+                  "BTC": 0.0031,
+                  "USD": 1000
+                },
+                "verified": true,
+                "type": "BUSINESS"
+              }
+            "#,
+        )
+        .unwrap()
+    }
 
-        // the Wyre test environment doesn't validate KYC but it does check
-        // that all the required fields are set and for document uploads the
-        // file must be valid.
-        //
-        // from: https://stackoverflow.com/questions/2253404/what-is-the-smallest-valid-jpeg-file-size-in-bytes
-        let smallest_jpeg: &[u8] = b"\
-            \xff\xd8\xff\xe0\x00\x10\x4a\x46\x49\x46\x00\x01\x01\x01\x00\x48\x00\
-            \x48\x00\x00\xff\xdb\x00\x43\x00\x03\x02\x02\x02\x02\x02\x03\x02\x02\
-            \x02\x03\x03\x03\x03\x04\x06\x04\x04\x04\x04\x04\x08\x06\x06\x05\x06\
-            \x09\x08\x0a\x0a\x09\x08\x09\x09\x0a\x0c\x0f\x0c\x0a\x0b\x0e\x0b\x09\
-            \x09\x0d\x11\x0d\x0e\x0f\x10\x10\x11\x10\x0a\x0c\x12\x13\x12\x10\x13\
-            \x0f\x10\x10\x10\xff\xc9\x00\x0b\x08\x00\x01\x00\x01\x01\x01\x11\x00\
-            \xff\xcc\x00\x06\x00\x10\x10\x05\xff\xda\x00\x08\x01\x01\x00\x00\x3f\
-            \x00\xd2\xcf\x20\xff\xd9";
+    #[test]
+    fn master_account_serde() {
+        use super::*;
+        let account = master_account_fixture();
 
-        let _upload_front = rt_01
-            .block_on(
-                wyre_client
-                    .upload_document(
-                        account.id.clone(),
-                        wyre::UploadDocument {
-                            field_id: wyre::ProfileFieldId::IndividualGovernmentId,
-                            document_type: Some(wyre::DocumentType::GovtId),
-                            document_sub_type: Some(wyre::DocumentSubType::Front),
-                            document: smallest_jpeg,
-                            content_type: "image/jpeg".to_string(),
-                        },
-                    )
-                    .boxed()
+        assert_eq!(account.status, AccountStatus::Approved);
+        assert_eq!(
+            account.available_balances[&Currency::USD],
+            Amount::from(1000)
+        );
+    }
+
+    #[test]
+    fn master_account_srn_limits() {
+        use super::*;
+        let account = master_account_fixture();
+
+        assert_eq!(account.srn_limits[0].period, LimitPeriod::Daily);
+        assert_eq!(account.srn_limits[0].amount, Amount::from(2500));
+    }
+
+    #[test]
+    fn limits_check() {
+        use super::*;
+        let limits = Limits::from(&master_account_fixture());
+        let account = SRN::Account("AC_XXXXXXXXXXX".to_owned());
+
+        assert!(limits
+            .check(&account, &Amount::from(2500), Currency::USD)
+            .is_ok());
+        assert!(limits
+            .check(&account, &Amount::from(3000), Currency::EUR)
+            .is_ok());
+
+        let error = limits
+            .check(&account, &Amount::from(3000), Currency::USD)
+            .unwrap_err();
+        assert_eq!(error.limit, limits.limits[0]);
+        assert_eq!(error.amount, Amount::from(3000));
+    }
+
+    #[test]
+    fn limits_check_other_srn() {
+        use super::*;
+        let limits = Limits::from(&master_account_fixture());
+        let payment_method = SRN::PaymentMethod("PA_XXXXXXXXXXX".to_owned());
+
+        assert!(limits
+            .check(&payment_method, &Amount::from(3000), Currency::USD)
+            .is_ok());
+    }
+
+    #[test]
+    fn master_account_identities() {
+        use super::*;
+        let account = master_account_fixture();
+
+        assert_eq!(account.identities[0].kind, IdentityType::Email);
+        assert_eq!(
+            account.identities[0].srn,
+            SRN::Email("test@sendwyre.com".to_owned())
+        );
+        assert_eq!(
+            account.identities[1].srn,
+            SRN::CellPhone("+15555555555".to_owned())
+        );
+        assert_eq!(account.identities[0].verified_at, Some(1497296436000));
+        assert!(!account.identities[1].verified);
+    }
+
+    #[test]
+    fn master_account_lock_status() {
+        use super::*;
+        let account = master_account_fixture();
+
+        assert_eq!(account.lock_status(), LockStatus::Unlocked);
+        let locked = MasterAccount {
+            locked_at: Some(1583511014000),
+            locked_reason: Some("Suspicious activity".to_owned()),
+            under_review_at: Some(1583510914000),
+            ..account
+        };
+        assert_eq!(
+            locked.lock_status(),
+            LockStatus::Locked {
+                since: 1583511014000,
+                reason: Some("Suspicious activity".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn master_account_display_currency() {
+        use super::*;
+        let account = master_account_fixture();
+
+        assert_eq!(account.profile.display_currency, Currency::USD);
+    }
+
+    fn preview_transfer_fixture() -> wyre::Transfer {
+        serde_json::from_str(
+            r#"
+            {
+              "id": "TF_VL4NAL9WH9A",
+              "owner": "account:AC_XXXXXXXXXXX",
+              "source": "account:AC_XXXXXXXXXXX",
+              "sourceAmount": 10,
+              "sourceCurrency": "USD",
+              "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+              "destAmount": 0.0049,
+              "destCurrency": "ETH",
+              "status": "PREVIEW",
+              "pendingSubStatus": null,
+              "createdAt": 1654635321327,
+              "completedAt": null,
+              "updatedAt": null,
+              "cancelledAt": null,
+              "expiresAt": 1654635351327,
+              "exchangeRate": 0.00049,
+              "fees": {
+                "ETH": 0.0001,
+                "USD": 0
+              },
+              "totalFees": 0.2,
+              "blockchainTx": null,
+              "message": "Thanks!",
+              "customId": "order-1234"
+            }
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn transfer_ledger_entry() {
+        use super::*;
+        let transfer = preview_transfer_fixture();
+
+        let entry = transfer.to_ledger_entry();
+        assert_eq!(entry.timestamp, 1654635321327);
+        assert_eq!(entry.total_fees, "0.2".parse::<Amount>().unwrap());
+        assert_eq!(entry.exchange_rate, Some("0.00049".parse().unwrap()));
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["transferId"], "TF_VL4NAL9WH9A");
+        assert_eq!(json["customId"], "order-1234");
+
+        let completed = Transfer {
+            completed_at: Some(1654635421327),
+            ..transfer
+        };
+        assert_eq!(completed.to_ledger_entry().timestamp, 1654635421327);
+    }
+
+    #[test]
+    fn transfer_as_create_transfer() {
+        let transfer = preview_transfer_fixture();
+
+        let request = transfer.as_create_transfer();
+        assert!(request.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "source": "account:AC_XXXXXXXXXXX",
+                "sourceAmount": "10",
+                "sourceCurrency": "USD",
+                "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                "destCurrency": "ETH",
+                "message": "Thanks!",
+                "customId": "order-1234",
+                "amountIncludesFees": true
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_serde() {
+        use super::*;
+        let transfers = serde_json::from_str::<Vec<Transfer>>(
+            r#"
+            [
+              {
+                "id": "TF_VL4NAL9WH9A",
+                "owner": "account:AC_XXXXXXXXXXX",
+                "source": "account:AC_XXXXXXXXXXX",
+                "sourceAmount": 10,
+                "sourceCurrency": "USD",
+                "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                "destAmount": 0.0049,
+                "destCurrency": "ETH",
+                "status": "PREVIEW",
+                "statusHistories": [],
+                "pendingSubStatus": null,
+                "failureReason": null,
+                "reversalReason": null,
+                "reversingSubStatus": null,
+                "createdAt": 1654635321327,
+                "completedAt": null,
+                "updatedAt": null,
+                "cancelledAt": null,
+                "expiresAt": 1654635351327,
+                "exchangeRate": 0.00049,
+                "fees": {
+                  "ETH": 0.0001,
+                  "USD": 0
+                },
+                "totalFees": 0.2,
+                "blockchainTx": null,
+                "message": null,
+                "customId": null
+              },
+              {
+                "id": "TF_ZC5EPMDHP3E",
+                "owner": "account:AC_XXXXXXXXXXX",
+                "source": "paymentmethod:PA_XXXXXXXXXXX:ach",
+                "sourceAmount": 20,
+                "sourceCurrency": "USD",
+                "dest": "account:AC_XXXXXXXXXXX",
+                "destAmount": 20,
+                "destCurrency": "USD",
+                "status": "PENDING",
+                "statusHistories": [
+                  {
+                    "id": "TF_ZC5EPMDHP3E-1",
+                    "transferId": "TF_ZC5EPMDHP3E",
+                    "createdAt": 1654635321327,
+                    "type": "OUTGOING",
+                    "statusOrder": 0,
+                    "statusDetail": "Initiating Transfer",
+                    "state": "INITIATED",
+                    "failedState": false
+                  }
+                ],
+                "pendingSubStatus": "AWAITING_FUNDS",
+                "createdAt": 1654635321327,
+                "completedAt": null,
+                "updatedAt": 1654635321327,
+                "cancelledAt": null,
+                "expiresAt": null,
+                "exchangeRate": null,
+                "fees": {
+                  "USD": 0
+                },
+                "totalFees": 0,
+                "message": null,
+                "customId": "order-1234"
+              },
+              {
+                "id": "TF_8PDNX79A2Q3",
+                "owner": "account:AC_XXXXXXXXXXX",
+                "source": "account:AC_XXXXXXXXXXX",
+                "sourceAmount": 0.01,
+                "sourceCurrency": "ETH",
+                "dest": "email:test@sendwyre.com",
+                "destAmount": 0.01,
+                "destCurrency": "ETH",
+                "destName": "Test User",
+                "status": "COMPLETED",
+                "pendingSubStatus": null,
+                "createdAt": 1654635321327,
+                "completedAt": 1654635421327,
+                "updatedAt": 1654635421327,
+                "cancelledAt": null,
+                "expiresAt": null,
+                "exchangeRate": null,
+                "fees": {
+                  "ETH": 0
+                },
+                "totalFees": 0,
+                "blockchainTx": {
+                  "id": "BTX_XXXXXXXXXXX",
+                  "networkTxId": "0x8a5a7e5f2f0c1a4b3d6e9f8c7b6a5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c",
+                  "currency": "ETH",
+                  "confirmations": 12
+                },
+                "message": "Thanks!",
+                "customId": null
+              },
+              {
+                "id": "TF_QWE4RTY6UIO",
+                "owner": "account:AC_XXXXXXXXXXX",
+                "source": "paymentmethod:PA_XXXXXXXXXXX:ach",
+                "sourceAmount": 20,
+                "sourceCurrency": "USD",
+                "dest": "account:AC_XXXXXXXXXXX",
+                "destAmount": 20,
+                "destCurrency": "USD",
+                "status": "FAILED",
+                "pendingSubStatus": null,
+                "failureReason": "R01 Insufficient Funds",
+                "createdAt": 1654635321327,
+                "completedAt": null,
+                "updatedAt": 1654835321327,
+                "cancelledAt": null,
+                "expiresAt": null,
+                "exchangeRate": null,
+                "fees": {
+                  "USD": 0
+                },
+                "totalFees": 0,
+                "message": null,
+                "customId": null
+              }
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let statuses: Vec<_> = transfers
+            .iter()
+            .map(|transfer| transfer.status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TransferStatus::Preview,
+                TransferStatus::Pending,
+                TransferStatus::Completed,
+                TransferStatus::Failed
+            ]
+        );
+
+        let mut by_status = HashMap::new();
+        for transfer in &transfers {
+            *by_status.entry(transfer.status.clone()).or_insert(0) += 1;
+        }
+        assert_eq!(by_status[&TransferStatus::Pending], 1);
+        assert_eq!(
+            transfers[1].source,
+            SRN::AchPaymentMethod("PA_XXXXXXXXXXX".to_owned())
+        );
+        assert_eq!(
+            transfers[1].pending_sub_status,
+            Some(PendingSubStatus::AwaitingFunds)
+        );
+        assert_eq!(transfers[2].dest_name.as_deref(), Some("Test User"));
+        assert_eq!(
+            transfers[2]
+                .blockchain_tx
+                .as_ref()
+                .and_then(|tx| tx.network_tx_id.as_deref()),
+            Some("0x8a5a7e5f2f0c1a4b3d6e9f8c7b6a5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c")
+        );
+        assert_eq!(
+            serde_json::from_str::<PendingSubStatus>(r#""AWAITING_REVIEW""#).unwrap(),
+            PendingSubStatus::Other("AWAITING_REVIEW".to_owned())
+        );
+    }
+
+    #[test]
+    fn unknown_status_round_trip() {
+        use super::*;
+
+        let status = serde_json::from_str::<TransferStatus>(r#""ON_HOLD""#).unwrap();
+        assert_eq!(status, TransferStatus::Other("ON_HOLD".to_owned()));
+        assert_eq!(serde_json::to_string(&status).unwrap(), r#""ON_HOLD""#);
+
+        let status = serde_json::from_str::<AccountStatus>(r#""SUSPENDED""#).unwrap();
+        assert_eq!(status, AccountStatus::Other("SUSPENDED".to_owned()));
+        assert_eq!(serde_json::to_string(&status).unwrap(), r#""SUSPENDED""#);
+
+        assert_eq!(
+            serde_json::to_string(&PaymentMethodStatus::AwaitingFollowup).unwrap(),
+            r#""AWAITING_FOLLOWUP""#
+        );
+    }
+
+    #[test]
+    fn create_payment_method_plaid_serde() {
+        use super::*;
+        let body = CreatePaymentMethod::PlaidPublicToken(CreatePlaidPaymentMethod {
+            plaid_public_token: "public-sandbox-X".to_owned(),
+            account_id: "ACCOUNT_X".to_owned(),
+        });
+
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "publicToken": "public-sandbox-X|ACCOUNT_X",
+                "paymentMethodType": "LOCAL_TRANSFER",
+                "country": "US"
+            })
+        );
+    }
+
+    #[test]
+    fn create_payment_method_bank_account_serde() {
+        use super::*;
+        let body = CreatePaymentMethod::BankAccount(Box::new(CreateBankAccountPaymentMethod {
+            currency: Currency::USD,
+            country: "US".to_owned(),
+            beneficiary_type: BeneficiaryType::Individual,
+            first_name_on_account: Some("John".to_owned()),
+            last_name_on_account: Some("Smith".to_owned()),
+            beneficiary_name: None,
+            beneficiary_address: "1 Main St".to_owned(),
+            beneficiary_address2: None,
+            beneficiary_city: "San Francisco".to_owned(),
+            beneficiary_postal: "94105".to_owned(),
+            beneficiary_phone_number: "+14155550100".to_owned(),
+            account_number: "0000000000".to_owned(),
+            routing_number: "000000000".to_owned(),
+            account_type: BankAccountType::Checking,
+        }));
+
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "paymentMethodType": "INTERNATIONAL_TRANSFER",
+                "paymentType": "LOCAL_BANK_WIRE",
+                "chargeablePM": true,
+                "currency": "USD",
+                "country": "US",
+                "beneficiaryType": "INDIVIDUAL",
+                "firstNameOnAccount": "John",
+                "lastNameOnAccount": "Smith",
+                "beneficiaryAddress": "1 Main St",
+                "beneficiaryCity": "San Francisco",
+                "beneficiaryPostal": "94105",
+                "beneficiaryPhoneNumber": "+14155550100",
+                "accountNumber": "0000000000",
+                "routingNumber": "000000000",
+                "accountType": "CHECKING"
+            })
+        );
+    }
+
+    #[test]
+    fn payment_method_list_serde() {
+        use super::*;
+        let list = serde_json::from_str::<PaymentMethodList>(
+            r#"
+            {
+              "data": [
+                {
+                  "id": "PA_W7YR28UH9Q3",
+                  "owner": "account:AC_XXXXXXXXXXX",
+                  "createdAt": 1654635321327,
+                  "name": "Plaid Checking 0000",
+                  "defaultCurrency": "USD",
+                  "status": "ACTIVE",
+                  "statusMessage": null,
+                  "waitingPrompts": [],
+                  "linkType": "LOCAL_TRANSFER",
+                  "beneficiaryType": "UNKNOWN",
+                  "supportsDeposits": true,
+                  "nameOnMethod": null,
+                  "last4Digits": "0000",
+                  "brand": null,
+                  "expirationDisplay": null,
+                  "countryCode": "US",
+                  "nickname": null,
+                  "rejectionMessage": null,
+                  "disabled": false,
+                  "supportsPayment": true,
+                  "chargeableCurrencies": ["USD"],
+                  "depositableCurrencies": ["USD"],
+                  "chargeFeeSchedule": null,
+                  "depositFeeSchedule": null,
+                  "minCharge": null,
+                  "maxCharge": null,
+                  "minDeposit": null,
+                  "maxDeposit": null,
+                  "documents": [],
+                  "srn": "paymentmethod:PA_W7YR28UH9Q3"
+                },
+                {
+                  "id": "PA_Q2VEDXHAVXM",
+                  "owner": "account:AC_XXXXXXXXXXX",
+                  "createdAt": 1654635321327,
+                  "name": "Chase 1234",
+                  "defaultCurrency": "USD",
+                  "status": "REJECTED",
+                  "statusMessage": "The name on the bank account does not match",
+                  "waitingPrompts": [],
+                  "linkType": "INTERNATIONAL_TRANSFER",
+                  "beneficiaryType": "INDIVIDUAL",
+                  "supportsDeposits": true,
+                  "last4Digits": "1234",
+                  "brand": null,
+                  "countryCode": "US",
+                  "rejectionMessage": "The name on the bank account does not match",
+                  "disabled": true,
+                  "supportsPayment": true,
+                  "chargeableCurrencies": ["USD"],
+                  "depositableCurrencies": ["USD"],
+                  "documents": [],
+                  "srn": "paymentmethod:PA_Q2VEDXHAVXM"
+                },
+                {
+                  "id": "PA_7YGHT4RD9MN",
+                  "owner": "account:AC_XXXXXXXXXXX",
+                  "createdAt": 1654635321327,
+                  "name": "Wells Fargo 5678",
+                  "defaultCurrency": "USD",
+                  "status": "AWAITING_FOLLOWUP",
+                  "statusMessage": "Please upload a bank statement",
+                  "waitingPrompts": [
+                    {
+                      "id": "WP_XXXXXXXXXXX",
+                      "type": "PAYMENT_METHOD_BANK_STATEMENT",
+                      "details": {}
+                    }
+                  ],
+                  "linkType": "INTERNATIONAL_TRANSFER",
+                  "beneficiaryType": "INDIVIDUAL",
+                  "supportsDeposits": true,
+                  "last4Digits": "5678",
+                  "brand": null,
+                  "countryCode": "US",
+                  "rejectionMessage": null,
+                  "disabled": false,
+                  "supportsPayment": true,
+                  "chargeableCurrencies": ["USD"],
+                  "depositableCurrencies": ["USD"],
+                  "documents": [],
+                  "srn": "paymentmethod:PA_7YGHT4RD9MN"
+                }
+              ],
+              "recordsTotal": 3,
+              "position": 0,
+              "recordsFiltered": 3
+            }
+            "#,
+        )
+        .unwrap();
+
+        let statuses: Vec<_> = list
+            .data
+            .iter()
+            .map(|method| method.status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PaymentMethodStatus::Active,
+                PaymentMethodStatus::Rejected,
+                PaymentMethodStatus::AwaitingFollowup
+            ]
+        );
+        assert!(list.data[1].rejection_message.is_some());
+        assert!(list.data[0].can_charge(Currency::USD));
+        assert!(!list.data[0].can_deposit(Currency::EUR));
+    }
+
+    /// A transfer of `amount` USD between two accounts, with every optional
+    /// field unset.
+    fn create_transfer(amount: wyre::Amount) -> wyre::CreateTransfer {
+        use super::*;
+
+        CreateTransfer {
+            source: SRN::Account("AC_X".to_owned()),
+            source_amount: Some(amount),
+            source_currency: Currency::USD,
+            dest: SRN::Account("AC_Y".to_owned()),
+            dest_amount: None,
+            dest_currency: None,
+            message: None,
+            notify_url: None,
+            auto_confirm: None,
+            custom_id: None,
+            amount_includes_fees: None,
+            preview: None,
+            mute_messages: None,
+            rewards_discount: None,
+            referrer_account_id: None,
+            reservation: None,
+        }
+    }
+
+    #[test]
+    fn amount_plain_notation() {
+        for amount in [BigDecimal::from(20), "2E+1".parse().unwrap()] {
+            let json = serde_json::to_value(create_transfer(amount)).unwrap();
+            assert_eq!(json["sourceAmount"], "20");
+            assert!(json.get("destAmount").is_none());
+            assert!(json.get("destCurrency").is_none());
+        }
+    }
+
+    #[test]
+    fn transfer_fees_side() {
+        let on_source =
+            serde_json::to_value(create_transfer(BigDecimal::from(20)).with_fees_on_source());
+        assert_eq!(on_source.unwrap()["amountIncludesFees"], false);
+        let on_dest =
+            serde_json::to_value(create_transfer(BigDecimal::from(20)).with_fees_on_dest());
+        assert_eq!(on_dest.unwrap()["amountIncludesFees"], true);
+    }
+
+    #[test]
+    fn transfer_referrer() {
+        let mut referred = create_transfer(BigDecimal::from(20));
+        let json = serde_json::to_value(&referred).unwrap();
+        assert!(json.get("referrerAccountId").is_none());
+        referred.referrer_account_id = Some("AC_Z".into());
+        let json = serde_json::to_value(&referred).unwrap();
+        assert_eq!(json["referrerAccountId"], "AC_Z");
+        assert!(json.get("rewardsDiscount").is_none());
+    }
+
+    #[test]
+    fn transfer_reservation() {
+        let mut transfer = create_transfer(BigDecimal::from(20));
+        assert!(serde_json::to_value(&transfer)
+            .unwrap()
+            .get("reservation")
+            .is_none());
+
+        transfer.reservation = Some("RES_X".to_owned());
+        assert_eq!(
+            serde_json::to_value(&transfer).unwrap()["reservation"],
+            "RES_X"
+        );
+    }
+
+    #[test]
+    fn transfer_dest_currency_compatibility() {
+        use super::*;
+
+        let mut transfer = CreateTransfer {
+            source: SRN::Account("AC_X".to_owned()),
+            source_amount: Some(BigDecimal::from(20)),
+            source_currency: Currency::USD,
+            dest: SRN::Bitcoin("14CriXWTRoJmQdBzdikw6tEmSuwxMozWWq".to_owned()),
+            dest_amount: None,
+            dest_currency: Some(Currency::USDC),
+            message: None,
+            notify_url: None,
+            auto_confirm: None,
+            custom_id: None,
+            amount_includes_fees: None,
+            preview: None,
+            mute_messages: None,
+            rewards_discount: None,
+            referrer_account_id: None,
+            reservation: None,
+        };
+        assert!(transfer.validate().is_err());
+
+        transfer.dest = SRN::Ethereum("0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341".to_owned());
+        assert!(transfer.validate().is_ok());
+
+        transfer.dest = SRN::Account("AC_Y".to_owned());
+        transfer.dest_currency = None;
+        assert!(transfer.validate().is_ok());
+    }
+
+    #[test]
+    fn update_account_and_await() {
+        const ACCOUNT: &str = r#"{
+            "id": "AC_X",
+            "status": "OPEN",
+            "type": "INDIVIDUAL",
+            "country": "US",
+            "createdAt": 1541789972000,
+            "updatedAt": 1541789972000,
+            "depositAddresses": {},
+            "totalBalances": {},
+            "availableBalances": {},
+            "profileFields": [
+                {
+                    "fieldId": "individualDateOfBirth",
+                    "fieldType": "DATE",
+                    "value": "1990-09-24",
+                    "note": "Date of birth doesn't match the government id",
+                    "status": "OPEN"
+                }
+            ]
+        }"#;
+        let update = || wyre::UpdateAccount {
+            profile_fields: vec![],
+        };
+        let runtime = Runtime10::new().unwrap();
+
+        let (client, transport) = mock_client(200, ACCOUNT);
+        let (account, rejected) = runtime
+            .block_on(client.update_account_and_await(
+                "AC_X".into(),
+                update(),
+                std::time::Duration::from_secs(5),
+            ))
+            .unwrap();
+        assert_eq!(account.status, wyre::AccountStatus::Open);
+        assert_eq!(
+            rejected,
+            vec![(
+                wyre::ProfileFieldId::IndividualDateOfBirth,
+                "Date of birth doesn't match the government id".to_owned()
+            )]
+        );
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        let pending: &'static str = Box::leak(
+            ACCOUNT
+                .replacen(r#""OPEN""#, r#""PENDING""#, 1)
+                .into_boxed_str(),
+        );
+        let (client, transport) = mock_client(200, pending);
+        let error = runtime
+            .block_on(client.update_account_and_await(
+                "AC_X".into(),
+                update(),
+                std::time::Duration::from_millis(50),
+            ))
+            .unwrap_err();
+        match error {
+            wyre::Error::Timeout { last_seen } => match *last_seen {
+                wyre::LastSeen::Account(account) => {
+                    assert_eq!(account.status, wyre::AccountStatus::Pending)
+                }
+                last_seen => panic!("unexpected last seen: {:?}", last_seen),
+            },
+            error => panic!("unexpected error: {}", error),
+        }
+        assert_eq!(transport.requests.lock().unwrap()[0].method(), "POST");
+    }
+
+    #[test]
+    fn extra_query() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let client = client.with_extra_query("sortBy", "createdAt");
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.list_transfers(None, 0, 20));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].uri().query(),
+            Some("offset=0&limit=20&sortBy=createdAt")
+        );
+    }
+
+    #[test]
+    fn create_users() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let users = ["a@example.com", "b@example.com", "c@example.com"]
+            .iter()
+            .map(|&email| ModifyUser::builder().email(email).build())
+            .collect();
+        let results = runtime.block_on(client.create_users(users, 2));
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_err));
+
+        let requests = transport.requests.lock().unwrap();
+        let emails: Vec<_> = requests
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+                body["fields"]["email"].as_str().unwrap().to_owned()
+            })
+            .collect();
+        assert_eq!(emails, ["a@example.com", "b@example.com", "c@example.com"]);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn with_proxy() {
+        let proxy = reqwest::Proxy::all("http://localhost:3128").unwrap();
+        let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+            .with_proxy(proxy)
+            .unwrap();
+
+        assert_eq!(client.proxies.len(), 1);
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn without_built_in_root_certificates() {
+        let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+            .without_built_in_root_certificates()
+            .unwrap();
+
+        assert!(!client.built_in_root_certificates);
+    }
+
+    #[test]
+    fn environment_round_trip() {
+        for environment in [wyre::Environment::Test, wyre::Environment::Production] {
+            assert_eq!(
+                environment
+                    .to_string()
+                    .parse::<wyre::Environment>()
+                    .unwrap(),
+                environment
+            );
+            assert_eq!(
+                serde_json::from_value::<wyre::Environment>(
+                    serde_json::to_value(environment).unwrap()
+                )
+                .unwrap(),
+                environment
+            );
+        }
+
+        assert_eq!(
+            serde_json::from_str::<wyre::Environment>(r#""prod""#).unwrap(),
+            wyre::Environment::Production
+        );
+        assert!(serde_json::from_str::<wyre::Environment>(r#""staging""#).is_err());
+    }
+
+    #[test]
+    fn with_environment() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let production = client
+            .clone()
+            .with_environment(wyre::Environment::Production);
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.get_master_account());
+        let _ = runtime.block_on(production.get_master_account());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].uri().host(), Some("api.testwyre.com"));
+        assert_eq!(requests[1].uri().host(), Some("api.sendwyre.com"));
+    }
+
+    #[test]
+    fn srn_ach_payment_method_serde() {
+        use super::*;
+
+        let ach = SRN::AchPaymentMethod("PA-X".to_owned());
+        let json = serde_json::to_string(&ach).unwrap();
+        assert_eq!(json, r#""paymentmethod:PA-X:ach""#);
+        assert_eq!(serde_json::from_str::<SRN>(&json).unwrap(), ach);
+
+        assert_eq!(
+            serde_json::from_str::<SRN>(r#""paymentmethod:PA-X""#).unwrap(),
+            SRN::PaymentMethod("PA-X".to_owned())
+        );
+    }
+
+    #[test]
+    fn srn_stellar_memo_serde() {
+        use super::*;
+
+        let stellar = SRN::Stellar {
+            address: "GCIE2K".to_owned(),
+            memo: Some("US_48MBN7LX9VY".to_owned()),
+        };
+        let json = serde_json::to_string(&stellar).unwrap();
+        assert_eq!(json, r#""stellar:GCIE2K:US_48MBN7LX9VY""#);
+        assert_eq!(serde_json::from_str::<SRN>(&json).unwrap(), stellar);
+
+        assert_eq!(
+            serde_json::from_str::<SRN>(r#""stellar:GCIE2K""#).unwrap(),
+            SRN::Stellar {
+                address: "GCIE2K".to_owned(),
+                memo: None,
+            }
+        );
+    }
+
+    #[test]
+    fn srn_unknown_kind_serde() {
+        use super::*;
+
+        let srn: SRN = "solana:7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            srn,
+            SRN::Unknown {
+                kind: "solana".to_owned(),
+                value: "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV".to_owned(),
+            }
+        );
+        assert_eq!(
+            srn.to_string(),
+            "solana:7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV"
+        );
+        assert!(":X".parse::<SRN>().is_err());
+
+        let transfer = serde_json::from_str::<Transfer>(
+            r#"
+            {
+              "id": "TF_VL4NAL9WH9A",
+              "owner": "account:AC_XXXXXXXXXXX",
+              "source": "account:AC_XXXXXXXXXXX",
+              "sourceAmount": 10,
+              "sourceCurrency": "USD",
+              "dest": "solana:7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV",
+              "destAmount": 10,
+              "destCurrency": "USDC",
+              "status": "PENDING",
+              "statusHistories": [],
+              "createdAt": 1654635321327,
+              "fees": {
+                "USD": 0
+              },
+              "totalFees": 0
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(transfer.dest, srn);
+        assert_eq!(
+            serde_json::to_value(&transfer.dest).unwrap(),
+            "solana:7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV"
+        );
+    }
+
+    #[test]
+    fn dry_run_redacts_ssn() {
+        use std::sync::{Arc, Mutex};
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+            .with_dry_run({
+                let requests = requests.clone();
+                move |request| requests.lock().unwrap().push(request.clone())
+            });
+
+        let runtime = Runtime10::new().unwrap();
+        let res = runtime.block_on(client.create_account(wyre::CreateAccount {
+            kind: wyre::AccountType::Individual,
+            country: "US".into(),
+            profile_fields: vec![wyre::CreateProfileField {
+                field_id: wyre::ProfileFieldId::IndividualSsn,
+                value: wyre::ProfileFieldType::String(Some("123-45-6789".into())),
+            }],
+            referrer_account_id: None,
+            subaccount: None,
+            disable_email: None,
+        }));
+
+        assert!(matches!(res, Err(wyre::Error::DryRun)));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].url, "https://api.testwyre.com/v3/accounts");
+
+        let body = requests[0].body.as_ref().unwrap();
+        assert!(!body.contains("123-45-6789"));
+        assert!(body.contains("********"));
+    }
+
+    #[test]
+    fn minimal_individual_account() {
+        let address = wyre::Address {
+            street1: Some("7819 E. Stonybrook St.".into()),
+            street2: None,
+            city: Some("Seattle".into()),
+            state: Some("WA".into()),
+            postal_code: Some("98111".into()),
+            country: Some("US".into()),
+        };
+        let mut account = wyre::CreateAccount::minimal_individual(
+            "US",
+            "Alice Loyd",
+            address,
+            "+12062108021",
+            "test@example.com",
+            "1990-09-24",
+            "123-45-6789".to_owned().into(),
+        );
+        assert!(account.validate().is_ok());
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["type"], "INDIVIDUAL");
+        assert_eq!(json["profileFields"].as_array().unwrap().len(), 6);
+        assert_eq!(json["profileFields"][5]["fieldType"], "STRING");
+
+        account.profile_fields[5].value = wyre::ProfileFieldType::Date(Some("123-45-6789".into()));
+        assert!(account.validate().is_err());
+    }
+
+    #[test]
+    fn ssn_profile_field() {
+        let field = wyre::CreateProfileField {
+            field_id: wyre::ProfileFieldId::IndividualSsn,
+            value: wyre::ProfileFieldType::Ssn("123-45-6789".to_owned().into()),
+        };
+
+        assert!(!format!("{:?}", field).contains("123-45-6789"));
+        assert_eq!(
+            serde_json::to_value(&field).unwrap(),
+            serde_json::json!({
+                "fieldId": "individualSsn",
+                "fieldType": "STRING",
+                "value": "123-45-6789",
+            })
+        );
+    }
+
+    #[test]
+    fn profile_field_serde() {
+        let fields = serde_json::json!([
+            {
+                "fieldId": "individualLegalName",
+                "fieldType": "STRING",
+                "value": "Alice Loyd",
+                "note": null,
+                "updatedT": 1654635321327u64,
+                "status": "APPROVED"
+            },
+            {
+                "fieldId": "individualSsn",
+                "fieldType": "STRING",
+                "value": null,
+                "note": "Please provide your SSN",
+                "updatedT": null,
+                "status": "OPEN"
+            },
+            {
+                "fieldId": "individualCellphoneNumber",
+                "fieldType": "CELLPHONE",
+                "value": "+12062108021",
+                "note": null,
+                "updatedT": null,
+                "status": "PENDING"
+            },
+            {
+                "fieldId": "individualEmail",
+                "fieldType": "EMAIL",
+                "value": "test@example.com",
+                "note": null,
+                "updatedT": null,
+                "status": "APPROVED"
+            },
+            {
+                "fieldId": "individualResidenceAddress",
+                "fieldType": "ADDRESS",
+                "value": {
+                    "street1": "7819 E. Stonybrook St.",
+                    "city": "Seattle",
+                    "state": "WA",
+                    "postalCode": "98111",
+                    "country": "US"
+                },
+                "note": null,
+                "updatedT": null,
+                "status": "APPROVED"
+            },
+            {
+                "fieldId": "individualDateOfBirth",
+                "fieldType": "DATE",
+                "value": "1990-09-24",
+                "note": null,
+                "updatedT": null,
+                "status": "APPROVED"
+            },
+            {
+                "fieldId": "individualGovernmentId",
+                "fieldType": "DOCUMENT",
+                "value": ["DO_1", "DO_2"],
+                "note": null,
+                "updatedT": null,
+                "status": "PENDING"
+            },
+            {
+                "fieldId": "individualProofOfAddress",
+                "fieldType": "DOCUMENT",
+                "value": [],
+                "note": null,
+                "updatedT": null,
+                "status": "OPEN"
+            },
+            {
+                "fieldId": "individualSourceOfFunds",
+                "fieldType": "PAYMENT_METHOD",
+                "value": "PA_1",
+                "note": null,
+                "updatedT": null,
+                "status": "APPROVED"
+            }
+        ]);
+
+        let parsed: Vec<wyre::ProfileField> = serde_json::from_value(fields.clone()).unwrap();
+        assert!(matches!(
+            parsed[1].value,
+            wyre::ProfileFieldType::String(None)
+        ));
+        assert!(matches!(
+            &parsed[6].value,
+            wyre::ProfileFieldType::Document(ids) if ids.len() == 2
+        ));
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), fields);
+
+        let empty_document: wyre::ProfileField = serde_json::from_value(serde_json::json!({
+            "fieldId": "individualGovernmentId",
+            "fieldType": "DOCUMENT",
+            "value": null,
+            "note": null,
+            "updatedT": null,
+            "status": "OPEN"
+        }))
+        .unwrap();
+        assert!(matches!(
+            &empty_document.value,
+            wyre::ProfileFieldType::Document(ids) if ids.is_empty()
+        ));
+
+        let create = vec![
+            wyre::CreateProfileField {
+                field_id: wyre::ProfileFieldId::IndividualResidenceAddress,
+                value: wyre::ProfileFieldType::Address(Some(wyre::Address {
+                    street1: Some("7819 E. Stonybrook St.".to_owned()),
+                    street2: None,
+                    city: Some("Seattle".to_owned()),
+                    state: Some("WA".to_owned()),
+                    postal_code: Some("98111".to_owned()),
+                    country: Some("US".to_owned()),
+                })),
+            },
+            wyre::CreateProfileField {
+                field_id: wyre::ProfileFieldId::IndividualGovernmentId,
+                value: wyre::ProfileFieldType::Document(vec!["DO_1".to_owned()]),
+            },
+        ];
+        let json = serde_json::to_value(&create).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "fieldId": "individualResidenceAddress",
+                    "fieldType": "ADDRESS",
+                    "value": {
+                        "street1": "7819 E. Stonybrook St.",
+                        "city": "Seattle",
+                        "state": "WA",
+                        "postalCode": "98111",
+                        "country": "US"
+                    }
+                },
+                {
+                    "fieldId": "individualGovernmentId",
+                    "fieldType": "DOCUMENT",
+                    "value": ["DO_1"]
+                }
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Vec<wyre::ProfileField>>(fields).unwrap(),
+            parsed
+        );
+
+        let reparsed: Vec<wyre::CreateProfileField> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), json);
+    }
+
+    #[test]
+    fn custom_transport() {
+        let (client, transport) = mock_client(
+            404,
+            r#"{
+                "exceptionId": "test-exception",
+                "type": "NotFoundException",
+                "transient": false
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let res = runtime.block_on(client.get_transfer("TF_X".into(), None));
+
+        match res {
+            Err(wyre::Error::Api(error)) => assert_eq!(error.kind, wyre::exception::NOT_FOUND),
+            res => panic!("unexpected result: {:?}", res.map(|transfer| transfer.id)),
+        }
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri().path(), "/v3/transfers/TF_X");
+        assert_eq!(
+            requests[0].headers()["x-api-version"],
+            wyre::DEFAULT_API_VERSION
+        );
+        assert!(requests[0].headers().get("accept-language").is_none());
+    }
+
+    #[test]
+    fn with_language() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let client = client.with_language("es");
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].headers()["accept-language"], "es");
+    }
+
+    #[test]
+    fn get_user_scope_query() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.get_user("US_X".into(), &[]));
+        let _ = runtime.block_on(client.get_user("US_X".into(), &[UserScope::Transfer]));
+        let _ = runtime
+            .block_on(client.get_user("US_X".into(), &[UserScope::Transfer, UserScope::ACH]));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].uri().query(), Some("masqueradeAs=user%3AUS_X"));
+        assert_eq!(
+            requests[1].uri().query(),
+            Some("masqueradeAs=user%3AUS_X&scopes=TRANSFER")
+        );
+        assert_eq!(
+            requests[2].uri().query(),
+            Some("masqueradeAs=user%3AUS_X&scopes=TRANSFER%2CACH")
+        );
+    }
+
+    #[test]
+    fn create_onboarding_url() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "url": "https://pay.testwyre.com/purchase?reservation=RES_X",
+                "reservation": "RES_X",
+                "expiresAt": 1654635321327
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let onboarding = runtime
+            .block_on(client.create_onboarding_url(wyre::CreateOnboardingUrl {
+                referrer_account_id: "AC_X".into(),
+                source_amount: None,
+                source_currency: Some(wyre::Currency::USD),
+                dest_currency: None,
+                dest: None,
+                redirect_url: None,
+                failure_redirect_url: None,
+                payment_method: None,
+                lock_fields: vec![],
+            }))
+            .unwrap();
+
+        assert_eq!(onboarding.reservation.as_deref(), Some("RES_X"));
+        assert_eq!(onboarding.expires_at, Some(1654635321327));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].uri().path(), "/v3/orders/reserve");
+    }
+
+    #[test]
+    fn account_locked_error() {
+        let (client, _) = mock_client(
+            400,
+            r#"{
+                "exceptionId": "test-exception",
+                "type": "AccountLockedException",
+                "transient": false
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let error = runtime
+            .block_on(client.get_transfer("TF_X".into(), None))
+            .unwrap_err();
+
+        assert!(matches!(error, wyre::Error::AccountLocked(_)));
+        assert!(error.requires_support());
+    }
+
+    #[test]
+    fn get_transfer_by_custom_id() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "data": [
+                    {
+                        "id": "TF_1",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "account:AC_Y",
+                        "destAmount": 10,
+                        "destCurrency": "USD",
+                        "status": "COMPLETED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0,
+                        "customId": null
+                    },
+                    {
+                        "id": "TF_2",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "account:AC_Y",
+                        "destAmount": 10,
+                        "destCurrency": "USD",
+                        "status": "COMPLETED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0,
+                        "customId": "order-1234"
+                    }
+                ],
+                "recordsTotal": 2,
+                "position": 0,
+                "recordsFiltered": 2
+            }"#,
+        );
+        let runtime = Runtime10::new().unwrap();
+
+        let found = runtime
+            .block_on(client.get_transfer_by_custom_id("order-1234", None))
+            .unwrap();
+        assert_eq!(found.map(|transfer| transfer.id), Some("TF_2".into()));
+
+        let missing = runtime
+            .block_on(client.get_transfer_by_custom_id("order-5678", None))
+            .unwrap();
+        assert!(missing.is_none());
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn confirm_unconfirmed_transfers() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "data": [
+                    {
+                        "id": "TF_1",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "UNCONFIRMED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    },
+                    {
+                        "id": "TF_2",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "COMPLETED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    }
+                ],
+                "recordsTotal": 2,
+                "position": 0,
+                "recordsFiltered": 2
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let results = runtime
+            .block_on(client.confirm_unconfirmed_transfers(None))
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.as_str(), "TF_1");
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].uri().path(), "/v3/transfers");
+        assert_eq!(requests[1].uri().path(), "/v3/transfers/TF_1/confirm");
+    }
+
+    #[test]
+    fn confirm_unconfirmed_transfers_per_page() {
+        let transport = std::sync::Arc::new(MockTransport {
+            responses: vec![
+                (
+                    200,
+                    r#"{
+                        "data": [
+                            {
+                                "id": "TF_1",
+                                "owner": "account:AC_X",
+                                "source": "account:AC_X",
+                                "sourceAmount": 10,
+                                "sourceCurrency": "USD",
+                                "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                                "destAmount": 0.005,
+                                "destCurrency": "ETH",
+                                "status": "UNCONFIRMED",
+                                "createdAt": 1654635321327,
+                                "expiresAt": 4102444800000,
+                                "fees": {},
+                                "totalFees": 0
+                            }
+                        ],
+                        "recordsTotal": 2,
+                        "position": 0,
+                        "recordsFiltered": 2
+                    }"#,
+                ),
+                (
+                    200,
+                    r#"{
+                        "id": "TF_1",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "PENDING",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    }"#,
+                ),
+                (
+                    200,
+                    r#"{
+                        "data": [
+                            {
+                                "id": "TF_2",
+                                "owner": "account:AC_X",
+                                "source": "account:AC_X",
+                                "sourceAmount": 10,
+                                "sourceCurrency": "USD",
+                                "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                                "destAmount": 0.005,
+                                "destCurrency": "ETH",
+                                "status": "UNCONFIRMED",
+                                "createdAt": 1654635321327,
+                                "expiresAt": 1654635351327,
+                                "fees": {},
+                                "totalFees": 0
+                            }
+                        ],
+                        "recordsTotal": 2,
+                        "position": 1,
+                        "recordsFiltered": 2
+                    }"#,
+                ),
+            ],
+            ..Default::default()
+        });
+        let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+            .with_transport(transport.clone());
+
+        let runtime = Runtime10::new().unwrap();
+        let results = runtime
+            .block_on(client.confirm_unconfirmed_transfers(None))
+            .unwrap();
+
+        // TF_2 had already expired, so only TF_1 is confirmed.
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.as_str(), "TF_1");
+        assert_eq!(
+            results[0].1.as_ref().unwrap().status,
+            wyre::TransferStatus::Pending
+        );
+
+        // TF_1 is confirmed before the second page is fetched.
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].uri().query(), Some("offset=0&limit=100"));
+        assert_eq!(requests[1].uri().path(), "/v3/transfers/TF_1/confirm");
+        assert_eq!(requests[2].uri().query(), Some("offset=1&limit=100"));
+    }
+
+    #[test]
+    fn empty_success_body() {
+        let (client, _) = mock_client(200, "");
+        let runtime = Runtime10::new().unwrap();
+
+        let request = wyre::RequestBuilder::post("https://api.testwyre.com/v3/x");
+        runtime.block_on(client.send::<()>(request)).unwrap();
+
+        let request = wyre::RequestBuilder::post("https://api.testwyre.com/v3/x");
+        let error = runtime
+            .block_on(client.send::<wyre::Transfer>(request))
+            .unwrap_err();
+        assert!(matches!(error, wyre::Error::Decode(_)));
+    }
+
+    #[test]
+    fn api_error_is_retryable() {
+        let error = |kind: &str, transient: bool| wyre::ApiError {
+            exception_id: "test-exception".to_owned(),
+            kind: kind.to_owned(),
+            error_code: None,
+            message: None,
+            language: None,
+            transient,
+            request_id: None,
+        };
+
+        assert!(error(wyre::exception::RATE_LIMIT, false).is_retryable());
+        assert!(error(wyre::exception::UNKNOWN, false).is_retryable());
+        assert!(error(wyre::exception::TRANSFER, true).is_retryable());
+        assert!(!error(wyre::exception::VALIDATION, false).is_retryable());
+        assert!(!error(wyre::exception::MFA_REQUIRED, true).is_retryable());
+        assert!(wyre::Error::Api(error(wyre::exception::MFA_REQUIRED, false)).is_mfa_required());
+    }
+
+    #[test]
+    fn api_error_display() {
+        let mut error = wyre::ApiError {
+            exception_id: "EX_123".to_owned(),
+            kind: wyre::exception::VALIDATION.to_owned(),
+            error_code: None,
+            message: Some("Invalid amount".to_owned()),
+            language: Some("en".to_owned()),
+            transient: false,
+            request_id: None,
+        };
+        assert_eq!(
+            error.to_string(),
+            "[EX_123] ValidationException: Invalid amount"
+        );
+
+        error.error_code = Some("transfer.amount.invalid".to_owned());
+        error.request_id = Some("REQ_1".to_owned());
+        assert_eq!(
+            wyre::Error::Unauthorized(error).to_string(),
+            "unauthorized: [EX_123] ValidationException/transfer.amount.invalid (request REQ_1): \
+             Invalid amount"
+        );
+    }
+
+    #[test]
+    fn rate_limit() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let client = client.with_rate_limit(4);
+        let runtime = Runtime10::new().unwrap();
+
+        let started = std::time::Instant::now();
+        for _ in 0..4 {
+            let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+        }
+        assert!(started.elapsed() < std::time::Duration::from_millis(200));
+
+        let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+        assert_eq!(transport.requests.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn rate_limit_shared_by_clones() {
+        let (client, _) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let client = client.with_rate_limit(4);
+        let clone = client.clone();
+        let runtime = Runtime10::new().unwrap();
+
+        let started = std::time::Instant::now();
+        for _ in 0..4 {
+            let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+        }
+        assert!(started.elapsed() < std::time::Duration::from_millis(200));
+
+        let _ = runtime.block_on(clone.get_transfer("TF_X".into(), None));
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+    }
+
+    #[test]
+    fn request_id_header() {
+        let runtime = Runtime10::new().unwrap();
+        let get_transfer = |status, body| {
+            let transport = std::sync::Arc::new(MockTransport {
+                status,
+                body,
+                headers: vec![("X-Request-Id", "REQ_1")],
+                ..Default::default()
+            });
+            let client = wyre::Client::new_from_str("key", "secret", wyre::Environment::Test)
+                .with_transport(transport);
+
+            runtime
+                .block_on(client.get_transfer("TF_X".into(), None))
+                .unwrap_err()
+        };
+
+        let error = get_transfer(
+            400,
+            r#"{
+                "exceptionId": "test-exception",
+                "type": "ValidationException",
+                "transient": false
+            }"#,
+        );
+        match error {
+            wyre::Error::Api(error) => assert_eq!(error.request_id.as_deref(), Some("REQ_1")),
+            error => panic!("unexpected error: {}", error),
+        }
+
+        let error = get_transfer(502, "<html><body>Bad Gateway</body></html>");
+        match error {
+            wyre::Error::Http { request_id, .. } => {
+                assert_eq!(request_id.as_deref(), Some("REQ_1"))
+            }
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn ping_unauthorized() {
+        let (client, transport) = mock_client(
+            401,
+            r#"{
+                "exceptionId": "test-exception",
+                "type": "AccessDeniedException",
+                "transient": false
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let error = runtime.block_on(client.ping()).unwrap_err();
+
+        assert!(matches!(error, wyre::Error::Unauthorized(_)));
+        assert!(error.is_unauthorized());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].uri().path(), "/v2/account");
+    }
+
+    #[test]
+    fn masquerade_query() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+        let _ = runtime.block_on(client.get_payment_methods(None, 0, 20));
+        let _ = runtime
+            .block_on(client.get_transfer("TF_X".into(), Some(wyre::SRN::User("US_X".to_owned()))));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].uri().query(), None);
+        assert_eq!(requests[1].uri().query(), Some("offset=0&limit=20"));
+        assert_eq!(requests[2].uri().query(), Some("masqueradeAs=user%3AUS_X"));
+        drop(requests);
+
+        let client = client.with_default_masquerade(wyre::SRN::Account("AC_X".to_owned()));
+        let _ = runtime.block_on(client.get_transfer("TF_X".into(), None));
+        let _ = runtime
+            .block_on(client.get_transfer("TF_X".into(), Some(wyre::SRN::User("US_X".to_owned()))));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(
+            requests[3].uri().query(),
+            Some("masqueradeAs=account%3AAC_X")
+        );
+        assert_eq!(requests[4].uri().query(), Some("masqueradeAs=user%3AUS_X"));
+
+        let manual = wyre::RequestBuilder::get("https://api.testwyre.com/v3/transfers")
+            .query(&[wyre::SRN::Account("AC_X".to_owned()).as_masquerade_param()])
+            .build()
+            .unwrap();
+        assert_eq!(manual.uri().query(), requests[3].uri().query());
+    }
+
+    #[test]
+    fn aggregate_balances() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "id": "WA_X",
+                "balances": {
+                    "BTC": 0.001,
+                    "USD": 10.5
+                }
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let total = runtime
+            .block_on(client.aggregate_balances(&[
+                wyre::SRN::Wallet("WA_1".to_owned()),
+                wyre::SRN::Wallet("WA_2".to_owned()),
+            ]))
+            .unwrap();
+
+        assert_eq!(total[&wyre::Currency::USD], "21".parse().unwrap());
+        assert_eq!(total[&wyre::Currency::BTC], "0.002".parse().unwrap());
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+
+        let error = runtime
+            .block_on(client.aggregate_balances(&[wyre::SRN::Email("a@b.c".to_owned())]))
+            .unwrap_err();
+        assert!(matches!(error, wyre::Error::Validation(_)));
+    }
+
+    #[test]
+    fn aggregate_balances_unknown_currency() {
+        let (client, _) = mock_client(
+            200,
+            r#"{
+                "id": "WA_X",
+                "balances": {
+                    "USD": 10.5,
+                    "XYZ": 3
+                }
+            }"#,
+        );
+
+        let error = Runtime10::new()
+            .unwrap()
+            .block_on(client.aggregate_balances(&[wyre::SRN::Wallet("WA_1".to_owned())]))
+            .unwrap_err();
+        assert!(matches!(error, wyre::Error::Validation(_)));
+    }
+
+    #[test]
+    fn await_payment_method_rejected() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "id": "PA_X",
+                "owner": "account:AC_X",
+                "createdAt": 1654635321327,
+                "name": "Plaid Checking 0000",
+                "defaultCurrency": "USD",
+                "status": "REJECTED",
+                "statusMessage": null,
+                "linkType": "LOCAL_TRANSFER",
+                "beneficiaryType": "UNKNOWN",
+                "last4Digits": "0000",
+                "countryCode": "US",
+                "rejectionMessage": "Account closed",
+                "disabled": true,
+                "supportsPayment": true,
+                "chargeableCurrencies": ["USD"],
+                "depositableCurrencies": ["USD"],
+                "srn": "paymentmethod:PA_X"
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let error = runtime
+            .block_on(client.await_payment_method_active(
+                "PA_X".into(),
+                None,
+                std::time::Duration::from_secs(60),
+            ))
+            .unwrap_err();
+
+        match error {
+            wyre::Error::PaymentMethodRejected(payment_method) => assert_eq!(
+                payment_method.rejection_message.as_deref(),
+                Some("Account closed")
+            ),
+            error => panic!("unexpected error: {}", error),
+        }
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri().path(), "/v2/paymentMethod/PA_X");
+    }
+
+    #[test]
+    fn await_payment_method_active_timeout() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                "id": "PA_X",
+                "owner": "account:AC_X",
+                "createdAt": 1654635321327,
+                "name": "Plaid Checking 0000",
+                "defaultCurrency": "USD",
+                "status": "PENDING",
+                "statusMessage": null,
+                "linkType": "LOCAL_TRANSFER",
+                "beneficiaryType": "UNKNOWN",
+                "last4Digits": "0000",
+                "countryCode": "US",
+                "rejectionMessage": null,
+                "disabled": false,
+                "supportsPayment": true,
+                "chargeableCurrencies": ["USD"],
+                "depositableCurrencies": ["USD"],
+                "srn": "paymentmethod:PA_X"
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let started = std::time::Instant::now();
+        let error = runtime
+            .block_on(client.await_payment_method_active(
+                "PA_X".into(),
+                None,
+                std::time::Duration::from_millis(50),
+            ))
+            .unwrap_err();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(error.is_timeout());
+        match error {
+            wyre::Error::Timeout { last_seen } => match *last_seen {
+                wyre::LastSeen::PaymentMethod(payment_method) => {
+                    assert_eq!(payment_method.status, wyre::PaymentMethodStatus::Pending)
+                }
+                last_seen => panic!("unexpected last seen: {:?}", last_seen),
+            },
+            error => panic!("unexpected error: {}", error),
+        }
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stream_transfers_ndjson() {
+        let (client, _) = mock_client(
+            200,
+            r#"{
+                "data": [
+                    {
+                        "id": "TF_1",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "UNCONFIRMED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    },
+                    {
+                        "id": "TF_2",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "COMPLETED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    }
+                ],
+                "recordsTotal": 2,
+                "position": 0,
+                "recordsFiltered": 2
+            }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let mut output = Vec::new();
+        let count = runtime
+            .block_on(client.stream_transfers_ndjson(None, &mut output))
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let lines: Vec<_> = std::str::from_utf8(&output).unwrap().lines().collect();
+        assert_eq!(lines.len(), 2);
+        let transfer: wyre::Transfer = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(transfer.id.as_str(), "TF_2");
+    }
+
+    #[test]
+    fn email_and_cellphone_transfer_dest() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let dests = [
+            wyre::SRN::Email("test@sendwyre.com".to_owned()),
+            wyre::SRN::CellPhone("+15555555555".to_owned()),
+        ];
+
+        for dest in &dests {
+            let _ = runtime.block_on(client.create_transfer(
+                wyre::CreateTransfer {
+                    source: wyre::SRN::Account("AC_X".to_owned()),
+                    source_amount: Some(BigDecimal::from(5)),
+                    source_currency: wyre::Currency::USD,
+                    dest: dest.clone(),
+                    dest_amount: None,
+                    dest_currency: None,
+                    message: Some("Enjoy!".to_owned()),
+                    notify_url: None,
+                    auto_confirm: Some(true),
+                    custom_id: None,
+                    amount_includes_fees: None,
+                    preview: None,
+                    mute_messages: None,
+                    rewards_discount: None,
+                    referrer_account_id: None,
+                    reservation: None,
+                },
+                None,
+            ));
+        }
+
+        let requests = transport.requests.lock().unwrap();
+        let body_dest = |request: &http::Request<Vec<u8>>| {
+            let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+            serde_json::from_value::<wyre::SRN>(body["dest"].clone()).unwrap()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&dests[0]).unwrap(),
+            "email:test@sendwyre.com"
+        );
+        assert_eq!(
+            serde_json::to_value(&dests[1]).unwrap(),
+            "cellphone:+15555555555"
+        );
+        assert_eq!(body_dest(&requests[0]), dests[0]);
+        assert_eq!(body_dest(&requests[1]), dests[1]);
+    }
+
+    #[test]
+    fn get_transfer_srn() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let payload: wyre::CallbackPayload<wyre::Transfer> =
+            serde_json::from_str(r#"{"subscriptionId": "DA_X", "trigger": "transfer:TF_X"}"#)
+                .unwrap();
+        let srn = match payload {
+            wyre::CallbackPayload::Metadata(metadata) => metadata.trigger,
+            wyre::CallbackPayload::Data(_) => panic!("expected metadata"),
+        };
+        let _ = runtime.block_on(client.get_transfer_srn(srn, None));
+        let error = runtime
+            .block_on(client.get_transfer_srn(wyre::SRN::Wallet("WA_X".to_owned()), None))
+            .unwrap_err();
+
+        assert!(matches!(error, wyre::Error::Validation(_)));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri().path(), "/v3/transfers/TF_X");
+    }
+
+    #[test]
+    fn upload_document_multipart() {
+        let (client, transport) = mock_client(404, r#"{"type": "NotFoundException"}"#);
+        let runtime = Runtime10::new().unwrap();
+
+        let _ = runtime.block_on(client.upload_document_multipart(
+            "AC_X".into(),
+            wyre::UploadDocument {
+                field_id: wyre::ProfileFieldId::IndividualGovernmentId,
+                document_type: Some(wyre::DocumentType::GovtId),
+                document_sub_type: Some(wyre::DocumentSubType::Front),
+                document: vec![0xff, 0xd8, 0xff, 0xd9],
+                content_type: "image/jpeg".to_owned(),
+            },
+        ));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].uri().path(),
+            "/v3/accounts/AC_X/individualGovernmentId"
+        );
+        assert_eq!(
+            requests[0].uri().query(),
+            Some("documentType=GOVT_ID&documentSubType=FRONT&masqueradeAs=AC_X")
+        );
+        assert!(requests[0].headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("multipart/form-data; boundary="));
+        drop(requests);
+
+        let one_sided = runtime.block_on(client.upload_document(
+            "AC_X".into(),
+            wyre::UploadDocument {
+                field_id: wyre::ProfileFieldId::IndividualGovernmentId,
+                document_type: Some(wyre::DocumentType::DrivingLicense),
+                document_sub_type: None,
+                document: vec![0xff, 0xd8, 0xff, 0xd9],
+                content_type: "image/jpeg".to_owned(),
+            },
+        ));
+        assert!(matches!(one_sided, Err(wyre::Error::Validation(_))));
+        assert!(!wyre::DocumentType::Passport.requires_both_sides());
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        let passport = runtime.block_on(client.upload_government_id(
+            "AC_X".into(),
+            wyre::DocumentType::Passport,
+            vec![0xff, 0xd8, 0xff, 0xd9],
+            vec![0xff, 0xd8, 0xff, 0xd9],
+            "image/jpeg",
+        ));
+        assert!(matches!(passport, Err(wyre::Error::Validation(_))));
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn await_transfer_terminal_timeout() {
+        let (client, transport) = mock_client(
+            200,
+            r#"{
+                        "id": "TF_1",
+                        "owner": "account:AC_X",
+                        "source": "account:AC_X",
+                        "sourceAmount": 10,
+                        "sourceCurrency": "USD",
+                        "dest": "ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341",
+                        "destAmount": 0.005,
+                        "destCurrency": "ETH",
+                        "status": "UNCONFIRMED",
+                        "createdAt": 1654635321327,
+                        "fees": {},
+                        "totalFees": 0
+                    }"#,
+        );
+
+        let runtime = Runtime10::new().unwrap();
+        let started = std::time::Instant::now();
+        let error = runtime
+            .block_on(client.await_transfer_terminal(
+                "TF_1".into(),
+                None,
+                std::time::Duration::from_millis(20),
+                std::time::Duration::from_millis(50),
+            ))
+            .unwrap_err();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(error.is_timeout());
+        match error {
+            wyre::Error::Timeout { last_seen } => match *last_seen {
+                wyre::LastSeen::Transfer(transfer) => {
+                    assert_eq!(transfer.status, wyre::TransferStatus::Unconfirmed)
+                }
+                last_seen => panic!("unexpected last seen: {:?}", last_seen),
+            },
+            error => panic!("unexpected error: {}", error),
+        }
+        assert!(transport.requests.lock().unwrap().len() >= 2);
+    }
+
+    #[test]
+    fn non_json_error_body() {
+        let (client, _) = mock_client(502, "<html><body>Bad Gateway</body></html>");
+
+        let runtime = Runtime10::new().unwrap();
+        let error = runtime
+            .block_on(client.get_transfer("TF_X".into(), None))
+            .unwrap_err();
+
+        match error {
+            wyre::Error::Http { status, body, .. } => {
+                assert_eq!(status, http::StatusCode::BAD_GATEWAY);
+                assert!(body.contains("Bad Gateway"));
+            }
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn can_create_ach_transfer_via_plaid() {
+        dotenv::dotenv().unwrap();
+
+        let rt_10 = Runtime10::new().unwrap();
+        let plaid_client = plaid::Client::new(
+            std::env::var("PLAID_CLIENT_ID").unwrap(),
+            std::env::var("PLAID_SECRET").unwrap(),
+            plaid::client::Environment::Sandbox,
+        );
+
+        let public_token_response = rt_10
+            .block_on(plaid_client.create_sandbox_public_token("ins_1", &["auth", "identity"]))
+            .unwrap();
+
+        let exchange_token_response = rt_10
+            .block_on(plaid_client.exchange_public_token(&public_token_response.public_token))
+            .unwrap();
+
+        let accounts_response = rt_10
+            .block_on(plaid_client.get_accounts(&exchange_token_response.access_token, None))
+            .unwrap();
+
+        let processor_token_response = rt_10
+            .block_on(plaid_client.create_processor_token(
+                &exchange_token_response.access_token,
+                &accounts_response.accounts[0].account_id,
+                "wyre",
+            ))
+            .unwrap();
+
+        let mut rt_01 = Runtime01::new().unwrap();
+        let wyre_client = client_from_env();
+        let wyre_client: &'static _ = Box::leak(Box::new(wyre_client));
+
+        let account = rt_01
+            .block_on(
+                wyre_client
+                    .create_account(wyre::CreateAccount {
+                        subaccount: Some(true),
+                        disable_email: Some(true),
+                        ..wyre::CreateAccount::minimal_individual(
+                            "US",
+                            "Alice Loyd",
+                            wyre::Address {
+                                street1: Some("7819 E. Stonybrook St.".into()),
+                                street2: None,
+                                city: Some("Seattle".into()),
+                                state: Some("WA".into()),
+                                postal_code: Some("98111".into()),
+                                country: Some("US".into()),
+                            },
+                            "+12062108021",
+                            "test@example.com",
+                            "1990-09-24",
+                            "123-45-6789".to_owned().into(),
+                        )
+                    })
+                    .boxed()
                     .compat(),
             )
             .unwrap();
 
-        let _upload_back = rt_01
+        // the Wyre test environment doesn't validate KYC but it does check
+        // that all the required fields are set and for document uploads the
+        // file must be valid.
+        //
+        // from: https://stackoverflow.com/questions/2253404/what-is-the-smallest-valid-jpeg-file-size-in-bytes
+        let smallest_jpeg: &[u8] = b"\
+            \xff\xd8\xff\xe0\x00\x10\x4a\x46\x49\x46\x00\x01\x01\x01\x00\x48\x00\
+            \x48\x00\x00\xff\xdb\x00\x43\x00\x03\x02\x02\x02\x02\x02\x03\x02\x02\
+            \x02\x03\x03\x03\x03\x04\x06\x04\x04\x04\x04\x04\x08\x06\x06\x05\x06\
+            \x09\x08\x0a\x0a\x09\x08\x09\x09\x0a\x0c\x0f\x0c\x0a\x0b\x0e\x0b\x09\
+            \x09\x0d\x11\x0d\x0e\x0f\x10\x10\x11\x10\x0a\x0c\x12\x13\x12\x10\x13\
+            \x0f\x10\x10\x10\xff\xc9\x00\x0b\x08\x00\x01\x00\x01\x01\x01\x11\x00\
+            \xff\xcc\x00\x06\x00\x10\x10\x05\xff\xda\x00\x08\x01\x01\x00\x00\x3f\
+            \x00\xd2\xcf\x20\xff\xd9";
+
+        let _upload_government_id = rt_01
             .block_on(
                 wyre_client
-                    .upload_document(
+                    .upload_government_id(
                         account.id.clone(),
-                        wyre::UploadDocument {
-                            field_id: wyre::ProfileFieldId::IndividualGovernmentId,
-                            document_type: Some(wyre::DocumentType::GovtId),
-                            document_sub_type: Some(wyre::DocumentSubType::Back),
-                            document: smallest_jpeg,
-                            content_type: "image/jpeg".to_string(),
-                        },
+                        wyre::DocumentType::GovtId,
+                        smallest_jpeg,
+                        smallest_jpeg,
+                        "image/jpeg",
                     )
                     .boxed()
                     .compat(),
             )
             .unwrap();
 
-        let _ach_payment_method = rt_01
+        let ach_payment_method = rt_01
             .block_on(
                 wyre_client
                     .create_ach_payment_method(
@@ -764,21 +3896,30 @@ mod tests {
                             payment_method_type: wyre::PaymentMethodType::LocalTransfer,
                             country: wyre::AchPaymentMethodCountry::US,
                         },
-                        Some(account.id.clone()),
+                        Some(account.srn()),
                     )
                     .boxed()
                     .compat(),
             )
             .unwrap();
 
-        // give the Wyre test environment a chance to activate the payment
-        // method
-        std::thread::sleep(std::time::Duration::from_secs(30));
+        rt_01
+            .block_on(
+                wyre_client
+                    .await_payment_method_active(
+                        ach_payment_method.id,
+                        Some(account.srn()),
+                        std::time::Duration::from_secs(60),
+                    )
+                    .boxed()
+                    .compat(),
+            )
+            .unwrap();
 
         let payment_methods = rt_01
             .block_on(
                 wyre_client
-                    .get_payment_methods(Some(account.id.clone()), 0, 10)
+                    .get_payment_methods(Some(account.srn()), 0, 10)
                     .boxed()
                     .compat(),
             )
@@ -795,10 +3936,14 @@ mod tests {
                 wyre_client
                     .create_transfer(
                         wyre::CreateTransfer {
-                            source: format!("paymentmethod:{}:ach", payment_methods.data[0].id),
+                            source: wyre::SRN::AchPaymentMethod(
+                                payment_methods.data[0].id.to_string(),
+                            ),
                             source_currency: wyre::Currency::USD,
-                            source_amount: Some(BigDecimal::try_from(20.00).unwrap()),
-                            dest: "ethereum:0xc12fae05cbe72a501540f260d6c49ddc6f9d9f4d".to_string(),
+                            source_amount: Some(BigDecimal::from(20.00)),
+                            dest: wyre::SRN::Ethereum(
+                                "0xc12fae05cbe72a501540f260d6c49ddc6f9d9f4d".to_owned(),
+                            ),
                             dest_currency: Some(wyre::Currency::USDC),
                             dest_amount: None,
                             message: Some("test transfer".into()),
@@ -808,8 +3953,11 @@ mod tests {
                             amount_includes_fees: Some(false),
                             preview: Some(false),
                             mute_messages: Some(true),
+                            rewards_discount: None,
+                            referrer_account_id: None,
+                            reservation: None,
                         },
-                        Some(account.id.clone()),
+                        Some(account.srn()),
                     )
                     .boxed()
                     .compat(),
@@ -819,7 +3967,7 @@ mod tests {
         let _transfer = rt_01
             .block_on(
                 wyre_client
-                    .get_transfer(created_transfer.id.clone(), Some(account.id.clone()))
+                    .get_transfer(created_transfer.id.clone(), Some(account.srn()))
                     .boxed()
                     .compat(),
             )
@@ -0,0 +1,110 @@
+//! Transfer limits applied to an account or user
+
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+use crate::account::MasterAccount;
+use crate::common::{open_enum, Amount, Currency};
+use crate::srn::SystemResourceName;
+
+/// The transfer limits that apply to an account, e.g. built from a
+/// [`MasterAccount`] with `Limits::from(&account)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Limits {
+    /// Every limit that applies to the account.
+    pub limits: Vec<SrnLimit>,
+}
+
+impl Limits {
+    /// Checks that `amount` in `currency` doesn't exceed any of the limits
+    /// placed on `srn` in that currency. Limits on other SRNs are ignored, so
+    /// check every SRN the transfer is subject to, e.g. both the account and
+    /// the source payment method.
+    #[allow(clippy::result_large_err)]
+    pub fn check(
+        &self,
+        srn: &SystemResourceName,
+        amount: &Amount,
+        currency: Currency,
+    ) -> Result<(), LimitError> {
+        let srn = srn.to_string();
+
+        match self
+            .limits
+            .iter()
+            .find(|limit| limit.srn == srn && limit.currency == currency && *amount > limit.amount)
+        {
+            Some(limit) => Err(LimitError {
+                limit: limit.clone(),
+                amount: amount.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<&MasterAccount> for Limits {
+    fn from(account: &MasterAccount) -> Self {
+        Limits {
+            limits: account.srn_limits.clone(),
+        }
+    }
+}
+
+/// A cap on the amount that can be transferred by an SRN over a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SrnLimit {
+    /// The SRN the limit applies to.
+    pub srn: String,
+
+    /// The period the limit is measured over.
+    pub period: LimitPeriod,
+
+    /// The currency the limit is denominated in.
+    pub currency: Currency,
+
+    /// The maximum amount that can be transferred in the period.
+    pub amount: Amount,
+}
+
+open_enum! {
+    /// See [`SrnLimit`].
+    #[allow(missing_docs)]
+    pub enum LimitPeriod {
+        Daily = "DAILY",
+        Weekly = "WEEKLY",
+        Monthly = "MONTHLY",
+        Yearly = "YEARLY",
+    }
+}
+
+/// Returned by [`Limits::check`] when an amount exceeds a limit.
+#[derive(Debug, Clone)]
+pub struct LimitError {
+    /// The limit that would be exceeded.
+    pub limit: SrnLimit,
+
+    /// The amount that was checked.
+    pub amount: Amount,
+}
+
+impl StdError for LimitError {}
+
+impl Display for LimitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} {:?} exceeds the {:?} limit of {} {:?} for {}",
+            self.amount,
+            self.limit.currency,
+            self.limit.period,
+            self.limit.amount,
+            self.limit.currency,
+            self.limit.srn
+        )
+    }
+}
@@ -0,0 +1,65 @@
+//! Hosted onboarding through the Wyre widget
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::common::{serialize_plain_amount, Amount, Currency};
+use crate::id::AccountId;
+use crate::srn::SystemResourceName;
+
+/// See [Wallet Order Reservations](https://docs.sendwyre.com/reference/walletorderreservations)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOnboardingUrl {
+    /// The account that referred the user, usually your own account.
+    pub referrer_account_id: AccountId,
+
+    /// The amount to withdraw from the source, in units of `source_currency`.
+    #[serde(
+        serialize_with = "serialize_plain_amount",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_amount: Option<Amount>,
+
+    /// The currency the user pays with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_currency: Option<Currency>,
+
+    /// The currency the user receives.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dest_currency: Option<Currency>,
+
+    /// Where the purchased funds are sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dest: Option<SystemResourceName>,
+
+    /// Where the user is sent after completing the flow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_url: Option<Url>,
+
+    /// Where the user is sent if the flow fails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_redirect_url: Option<Url>,
+
+    /// The payment method offered to the user, e.g. `debit-card`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method: Option<String>,
+
+    /// Fields the user can't change in the widget, e.g. `amount`.
+    pub lock_fields: Vec<String>,
+}
+
+/// A hosted onboarding url, see [`Client::create_onboarding_url`](crate::Client::create_onboarding_url).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingUrl {
+    /// The url to send the user to.
+    pub url: String,
+
+    /// The reservation the url is tied to.
+    pub reservation: Option<String>,
+
+    /// When the url stops working, in milliseconds since the epoch. A new
+    /// url has to be requested after this.
+    pub expires_at: Option<u64>,
+}
@@ -1,27 +1,28 @@
 //! This module corresponds to the [Payment Method API](https://docs.sendwyre.com/docs/payment-method-overview)
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
-use crate::common::Currency;
+use crate::common::{open_enum, Currency};
+use crate::id::PaymentMethodId;
 
-/// See [Payment Method Statuses](https://docs.sendwyre.com/docs/payment-method-overview#payment-method-statuses).
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum PaymentMethodStatus {
-    /// Payment Method has not been activated and is PENDING review on Wyre's
-    /// side. No user action is required.
-    Pending,
+open_enum! {
+    /// See [Payment Method Statuses](https://docs.sendwyre.com/docs/payment-method-overview#payment-method-statuses).
+    pub enum PaymentMethodStatus {
+        /// Payment Method has not been activated and is PENDING review on Wyre's
+        /// side. No user action is required.
+        Pending = "PENDING",
 
-    /// Payment Method requires additional information from the user before
-    /// being useful. The case where you would see this is on WIRE_TRANSFER
-    /// payment methods when the bank statement is still required.
-    AwaitingFollowup,
+        /// Payment Method requires additional information from the user before
+        /// being useful. The case where you would see this is on WIRE_TRANSFER
+        /// payment methods when the bank statement is still required.
+        AwaitingFollowup = "AWAITING_FOLLOWUP",
 
-    /// Payment Method is active and ready for use.
-    Active,
+        /// Payment Method is active and ready for use.
+        Active = "ACTIVE",
 
-    /// Payment Method has been rejected by Wyre and cannot be used.
-    Rejected,
+        /// Payment Method has been rejected by Wyre and cannot be used.
+        Rejected = "REJECTED",
+    }
 }
 
 /// See [Payment Method Types](https://docs.sendwyre.com/docs/payment-method-overview#payment-method-types).
@@ -37,7 +38,7 @@ pub enum PaymentMethodType {
 }
 
 /// See [ACH - Create Payment Method - Parameters](https://docs.sendwyre.com/docs/ach-create-payment-method-processor-token-model#parameters)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateAchPaymentMethod {
     /// Token from Plaid via the `/processor/token/create` endpoint
@@ -59,19 +60,152 @@ pub enum AchPaymentMethodCountry {
     US,
 }
 
+/// See [Create Payment Method](https://docs.sendwyre.com/docs/create-payment-method)
+/// for the models that don't use a Plaid processor token (see
+/// [`CreateAchPaymentMethod`] for that one).
+///
+/// Serializes to the request body Wyre expects for the chosen model.
+#[derive(Debug, Clone)]
+pub enum CreatePaymentMethod {
+    /// Links a US bank account for ACH using a Plaid Link public token.
+    PlaidPublicToken(CreatePlaidPaymentMethod),
+
+    /// Links a bank account for wire transfers using its details.
+    BankAccount(Box<CreateBankAccountPaymentMethod>),
+}
+
+impl Serialize for CreatePaymentMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PlaidPublicTokenBody {
+            public_token: String,
+            payment_method_type: &'static str,
+            country: &'static str,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BankAccountBody<'a> {
+            payment_method_type: &'static str,
+            payment_type: &'static str,
+            #[serde(rename = "chargeablePM")]
+            chargeable_pm: bool,
+            #[serde(flatten)]
+            details: &'a CreateBankAccountPaymentMethod,
+        }
+
+        match self {
+            CreatePaymentMethod::PlaidPublicToken(plaid) => PlaidPublicTokenBody {
+                public_token: format!("{}|{}", plaid.plaid_public_token, plaid.account_id),
+                payment_method_type: "LOCAL_TRANSFER",
+                country: "US",
+            }
+            .serialize(serializer),
+            CreatePaymentMethod::BankAccount(details) => BankAccountBody {
+                payment_method_type: "INTERNATIONAL_TRANSFER",
+                payment_type: "LOCAL_BANK_WIRE",
+                chargeable_pm: true,
+                details,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+/// See [`CreatePaymentMethod::PlaidPublicToken`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePlaidPaymentMethod {
+    /// The public token returned by Plaid Link.
+    pub plaid_public_token: String,
+
+    /// The id of the Plaid account selected in Plaid Link.
+    pub account_id: String,
+}
+
+/// See [`CreatePaymentMethod::BankAccount`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBankAccountPaymentMethod {
+    /// The currency of the bank account.
+    pub currency: Currency,
+
+    /// The country of the bank account (alpha-2 country code).
+    pub country: String,
+
+    /// Whether the account is owned by an individual or a corporation.
+    pub beneficiary_type: BeneficiaryType,
+
+    /// The first name of the account holder (individuals only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name_on_account: Option<String>,
+
+    /// The last name of the account holder (individuals only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name_on_account: Option<String>,
+
+    /// The name of the corporation that owns the account (corporations only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beneficiary_name: Option<String>,
+
+    /// The street address of the account holder.
+    pub beneficiary_address: String,
+
+    /// Additional street address of the account holder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beneficiary_address2: Option<String>,
+
+    /// The city of the account holder.
+    pub beneficiary_city: String,
+
+    /// The postal code of the account holder.
+    pub beneficiary_postal: String,
+
+    /// The phone number of the account holder.
+    pub beneficiary_phone_number: String,
+
+    /// The bank account number.
+    pub account_number: String,
+
+    /// The bank routing number.
+    pub routing_number: String,
+
+    /// The type of bank account.
+    pub account_type: BankAccountType,
+}
+
+/// See [`CreateBankAccountPaymentMethod`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(missing_docs)]
+pub enum BeneficiaryType {
+    Individual,
+    Corporate,
+}
+
+/// See [`CreateBankAccountPaymentMethod`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(missing_docs)]
+pub enum BankAccountType {
+    Checking,
+    Savings,
+}
+
 /// See [ACH - Create Payment Method - Result Format](https://docs.sendwyre.com/docs/ach-create-payment-method-processor-token-model#result-format)
 /// and [Create Payment Method - Result Format](https://docs.sendwyre.com/docs/create-payment-method#result-format).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct PaymentMethod {
-    pub id: String,
+    pub id: PaymentMethodId,
     pub owner: String,
     pub created_at: u64,
     pub name: String,
     pub default_currency: Currency,
     pub status: PaymentMethodStatus,
-    // pub status_message: ???,
+    pub status_message: Option<String>,
     // pub waiting_prompts: Vec<???>,
     pub link_type: String,
     pub beneficiary_type: String,
@@ -82,7 +216,7 @@ pub struct PaymentMethod {
     // pub expirationDisplay: ???,
     pub country_code: String,
     // pub nickname: ???,
-    // pub rejectionMessage: ???,
+    pub rejection_message: Option<String>,
     pub disabled: bool,
     pub supports_payment: bool,
     pub chargeable_currencies: Vec<Currency>,
@@ -97,8 +231,22 @@ pub struct PaymentMethod {
     pub srn: String,
 }
 
+impl PaymentMethod {
+    /// Returns `true` if the payment method can be charged in `currency`,
+    /// i.e. used as a transfer source.
+    pub fn can_charge(&self, currency: Currency) -> bool {
+        self.chargeable_currencies.contains(&currency)
+    }
+
+    /// Returns `true` if `currency` can be deposited to the payment method,
+    /// i.e. used as a transfer destination.
+    pub fn can_deposit(&self, currency: Currency) -> bool {
+        self.depositable_currencies.contains(&currency)
+    }
+}
+
 /// See [List Payment Methods - Result Format](https://docs.sendwyre.com/docs/list-payment-methods#result-format)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct PaymentMethodList {
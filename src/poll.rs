@@ -0,0 +1,21 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use futures_timer::Delay;
+
+/// Waits `poll_interval` before the next poll of a polling helper, without
+/// sleeping past `deadline`. Up to a tenth of the interval is added at random
+/// so that clients started together don't poll in lockstep.
+pub(crate) async fn wait(poll_interval: Duration, deadline: Instant) {
+    let jitter = poll_interval.mul_f64(random_fraction() / 10.0);
+    let remaining = deadline.saturating_duration_since(Instant::now());
+
+    Delay::new((poll_interval + jitter).min(remaining)).await;
+}
+
+/// A number in `[0, 1)`, random enough for jitter.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,54 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use futures_timer::Delay;
+
+/// A token bucket shared between clones of a [`Client`](crate::Client), see
+/// [`Client::with_rate_limit`](crate::Client::with_rate_limit).
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    per_second: u32,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_second: u32) -> RateLimiter {
+        RateLimiter {
+            per_second,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(per_second),
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until a request can be made without exceeding the limit.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                let capacity = f64::from(self.per_second);
+                bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+                bucket.refilled_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+
+                Duration::from_secs_f64((1.0 - bucket.tokens) / capacity)
+            };
+
+            Delay::new(wait).await;
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Masking of sensitive values in request bodies before they are logged or
+//! reported.
+
+use serde_json::Value;
+
+/// The profile field ids whose values must never be written out.
+const REDACTED_FIELD_IDS: &[&str] = &["individualSsn"];
+
+/// Object keys whose values must never be written out (matched
+/// case-insensitively against any part of the key).
+const REDACTED_KEY_PARTS: &[&str] = &["secret", "token", "password", "ssn"];
+
+/// Renders a request body with the values of sensitive profile fields and
+/// secret-like keys masked.
+pub(crate) fn redact_body(bytes: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes>", bytes.len()),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let redact = map
+                .get("fieldId")
+                .and_then(Value::as_str)
+                .is_some_and(|field_id| REDACTED_FIELD_IDS.contains(&field_id));
+
+            for (key, value) in map.iter_mut() {
+                if (redact && key == "value") || is_secret_key(key) {
+                    *value = Value::String("********".to_owned());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Returns `true` for keys like `plaidProcessorToken` or `apiSecret`.
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+
+    REDACTED_KEY_PARTS.iter().any(|part| key.contains(part))
+}
@@ -0,0 +1,162 @@
+//! Building requests independently of the HTTP backend, see
+//! [`HttpTransport`](crate::HttpTransport).
+
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+
+use http::header::{HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderMap, Method, Request};
+use serde::Serialize;
+use url::Url;
+
+use crate::error::Error;
+
+/// Builds an `http::Request` with a buffered body, mirroring the parts of
+/// `reqwest::RequestBuilder` the client uses. The first error is kept and
+/// returned by [`RequestBuilder::build`].
+#[derive(Debug)]
+pub(crate) struct RequestBuilder {
+    request: Result<(Method, Url, HeaderMap, Vec<u8>), Error>,
+}
+
+impl RequestBuilder {
+    pub(crate) fn get(url: &str) -> RequestBuilder {
+        RequestBuilder::new(Method::GET, url)
+    }
+
+    pub(crate) fn post(url: &str) -> RequestBuilder {
+        RequestBuilder::new(Method::POST, url)
+    }
+
+    fn new(method: Method, url: &str) -> RequestBuilder {
+        RequestBuilder {
+            request: Url::parse(url)
+                .map(|url| (method, url, HeaderMap::new(), Vec::new()))
+                .map_err(encode_error),
+        }
+    }
+
+    /// Appends `query` to the query string, skipping `None` values.
+    pub(crate) fn query<T: Serialize + ?Sized>(mut self, query: &T) -> RequestBuilder {
+        if let Ok((_, url, _, _)) = &mut self.request {
+            match serde_urlencoded::to_string(query) {
+                Ok(encoded) if encoded.is_empty() => {}
+                Ok(encoded) => {
+                    let query = match url.query() {
+                        Some(existing) if !existing.is_empty() => {
+                            format!("{}&{}", existing, encoded)
+                        }
+                        _ => encoded,
+                    };
+                    url.set_query(Some(&query));
+                }
+                Err(error) => self.request = Err(encode_error(error)),
+            }
+        }
+        self
+    }
+
+    pub(crate) fn header<K, V>(mut self, name: K, value: V) -> RequestBuilder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        if let Ok((_, _, headers, _)) = &mut self.request {
+            match HeaderName::try_from(name)
+                .map_err(Into::into)
+                .and_then(|name| {
+                    HeaderValue::try_from(value)
+                        .map(|value| (name, value))
+                        .map_err(Into::into)
+                }) {
+                Ok((name, value)) => {
+                    headers.append(name, value);
+                }
+                Err(error) => self.request = Err(encode_error(error)),
+            }
+        }
+        self
+    }
+
+    pub(crate) fn bearer_auth(self, token: &str) -> RequestBuilder {
+        match HeaderValue::try_from(format!("Bearer {}", token)) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                self.header(AUTHORIZATION, value)
+            }
+            Err(error) => RequestBuilder {
+                request: Err(encode_error(error)),
+            },
+        }
+    }
+
+    pub(crate) fn body(mut self, bytes: Vec<u8>) -> RequestBuilder {
+        if let Ok((_, _, _, body)) = &mut self.request {
+            *body = bytes;
+        }
+        self
+    }
+
+    /// Sends `body` as JSON.
+    pub(crate) fn json<T: Serialize + ?Sized>(self, body: &T) -> RequestBuilder {
+        match serde_json::to_vec(body) {
+            Ok(bytes) => self.header(CONTENT_TYPE, "application/json").body(bytes),
+            Err(error) => RequestBuilder {
+                request: Err(encode_error(error)),
+            },
+        }
+    }
+
+    /// Sends `bytes` as the file `name` of a `multipart/form-data` body.
+    pub(crate) fn multipart_file(
+        self,
+        name: &str,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> RequestBuilder {
+        let boundary = boundary();
+
+        let mut body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            boundary, name, file_name, content_type
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        self.header(
+            CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(body)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn build(self) -> Result<Request<Vec<u8>>, Error> {
+        let (method, url, headers, body) = self.request?;
+
+        let mut request = Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .body(body)
+            .map_err(encode_error)?;
+        *request.headers_mut() = headers;
+
+        Ok(request)
+    }
+}
+
+/// A random multipart boundary, long enough not to appear in the document.
+fn boundary() -> String {
+    let random = || RandomState::new().build_hasher().finish();
+
+    format!("{:016x}{:016x}", random(), random())
+}
+
+fn encode_error<E: std::error::Error + Send + Sync + 'static>(error: E) -> Error {
+    Error::Encode(Box::new(error))
+}
@@ -0,0 +1,194 @@
+//! See [System Resource Names](https://docs.sendwyre.com/docs/srns)
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::Currency;
+
+/// Shorthand for [`SystemResourceName`].
+pub type SRN = SystemResourceName;
+
+/// An SRN is a typed URI used by Wyre to reference any entity, e.g.
+/// `account:AC_XXXXXXXX` or `ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SystemResourceName {
+    /// A Wyre account (`account:AC_XXXXXXXX`).
+    Account(String),
+
+    /// A Wyre wallet (`wallet:WA_XXXXXXXX`).
+    Wallet(String),
+
+    /// A Wyre user (`user:US_XXXXXXXX`).
+    User(String),
+
+    /// A transfer (`transfer:TF_XXXXXXXX`).
+    Transfer(String),
+
+    /// A payment method (`paymentmethod:PA_XXXXXXXX`).
+    PaymentMethod(String),
+
+    /// A payment method used as an ACH transfer source
+    /// (`paymentmethod:PA_XXXXXXXX:ach`).
+    AchPaymentMethod(String),
+
+    /// An email address (`email:test@sendwyre.com`).
+    Email(String),
+
+    /// A cellphone number (`cellphone:+15555555555`).
+    CellPhone(String),
+
+    /// A bitcoin address (`bitcoin:14CriXWTRoJmQdBzdikw6tEmSuwxMozWWq`).
+    Bitcoin(String),
+
+    /// An ethereum address (`ethereum:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341`).
+    Ethereum(String),
+
+    /// A stellar address with an optional memo (`stellar:ADDRESS` or
+    /// `stellar:ADDRESS:MEMO`). Transfers from external Stellar addresses
+    /// into a Wyre wallet require the User ID in the memo.
+    Stellar {
+        /// The stellar address
+        address: String,
+
+        /// The memo attached to the transfer
+        memo: Option<String>,
+    },
+
+    /// An avalanche address (`avalanche:0x9E01E0E60dF079136a7a1d4ed97d709D5Fe3e341`).
+    Avalanche(String),
+
+    /// An SRN of a kind this crate doesn't know about, kept as is so that it
+    /// round-trips unchanged.
+    Unknown {
+        /// The part before the first `:`, e.g. `solana`.
+        kind: String,
+
+        /// The rest of the SRN.
+        value: String,
+    },
+}
+
+impl SystemResourceName {
+    /// The query parameter that makes a request act as this SRN, as sent by
+    /// the client's methods. Useful to call an endpoint the crate doesn't
+    /// cover, e.g. `request.query(&[srn.as_masquerade_param()])`.
+    #[must_use]
+    pub fn as_masquerade_param(&self) -> (&'static str, String) {
+        ("masqueradeAs", self.to_string())
+    }
+
+    /// The currencies that can be sent to this SRN, or `None` if it isn't a
+    /// blockchain address and any currency can be sent to it (e.g. an
+    /// account, a wallet or an email address) or if its kind is unknown.
+    #[must_use]
+    pub fn compatible_currencies(&self) -> Option<&'static [Currency]> {
+        use Currency::*;
+        use SystemResourceName::*;
+
+        match self {
+            Bitcoin(_) => Some(&[BTC]),
+            Ethereum(_) => Some(&[
+                ETH, DAI, USDC, USDT, BUSD, GUSD, PAX, USDS, AAVE, COMP, LINK, WBTC, BAT, CRV, MKR,
+                SNX, UMA, UNI, YFI, GYEN, ZUSD,
+            ]),
+            Stellar { .. } => Some(&[XLM, SUSDC]),
+            Avalanche(_) => Some(&[AVAX]),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SystemResourceName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        use SystemResourceName::*;
+
+        match self {
+            Account(id) => write!(f, "account:{}", id),
+            Wallet(id) => write!(f, "wallet:{}", id),
+            User(id) => write!(f, "user:{}", id),
+            Transfer(id) => write!(f, "transfer:{}", id),
+            PaymentMethod(id) => write!(f, "paymentmethod:{}", id),
+            AchPaymentMethod(id) => write!(f, "paymentmethod:{}:ach", id),
+            Email(email) => write!(f, "email:{}", email),
+            CellPhone(number) => write!(f, "cellphone:{}", number),
+            Bitcoin(address) => write!(f, "bitcoin:{}", address),
+            Ethereum(address) => write!(f, "ethereum:{}", address),
+            Stellar {
+                address,
+                memo: None,
+            } => write!(f, "stellar:{}", address),
+            Stellar {
+                address,
+                memo: Some(memo),
+            } => write!(f, "stellar:{}:{}", address, memo),
+            Avalanche(address) => write!(f, "avalanche:{}", address),
+            Unknown { kind, value } => write!(f, "{}:{}", kind, value),
+        }
+    }
+}
+
+impl FromStr for SystemResourceName {
+    type Err = SrnParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use SystemResourceName::*;
+
+        let (kind, value) = match s.find(':') {
+            Some(index) if index > 0 && index + 1 < s.len() => {
+                (&s[..index], s[index + 1..].to_owned())
+            }
+            _ => return Err(SrnParseError(s.to_owned())),
+        };
+
+        match kind {
+            "account" => Ok(Account(value)),
+            "wallet" => Ok(Wallet(value)),
+            "user" => Ok(User(value)),
+            "transfer" => Ok(Transfer(value)),
+            "paymentmethod" => match value.strip_suffix(":ach") {
+                Some(id) => Ok(AchPaymentMethod(id.to_owned())),
+                None => Ok(PaymentMethod(value)),
+            },
+            "email" => Ok(Email(value)),
+            "cellphone" => Ok(CellPhone(value)),
+            "bitcoin" => Ok(Bitcoin(value)),
+            "ethereum" => Ok(Ethereum(value)),
+            "stellar" => match value.find(':') {
+                Some(index) => Ok(Stellar {
+                    address: value[..index].to_owned(),
+                    memo: Some(value[index + 1..].to_owned()),
+                }),
+                None => Ok(Stellar {
+                    address: value,
+                    memo: None,
+                }),
+            },
+            "avalanche" => Ok(Avalanche(value)),
+            kind => Ok(Unknown {
+                kind: kind.to_owned(),
+                value,
+            }),
+        }
+    }
+}
+
+impl Serialize for SystemResourceName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SystemResourceName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(|SrnParseError(srn)| DeError::custom(format!("invalid srn: {}", srn)))
+    }
+}
+
+/// Could not parse an SRN, contains the original string.
+#[derive(Debug)]
+pub struct SrnParseError(pub String);
@@ -3,18 +3,26 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::common::{Amount, Currency};
+use crate::common::{open_enum, serialize_plain_amount, Amount, Currency};
+use crate::error::ValidationError;
+use crate::id::{AccountId, TransferId};
+use crate::srn::SystemResourceName;
 
 /// See [Create Transfer - Parameters](https://docs.sendwyre.com/docs/create-transfer#parameters)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTransfer {
     /// An SRN representing an account that the funds will be retrieved from.
-    pub source: String,
+    pub source: SystemResourceName,
 
     /// The amount to withdrawal from the source, in units of `sourceCurrency`.
     /// Only include `sourceAmount` OR `destAmount`, not both.
+    #[serde(
+        serialize_with = "serialize_plain_amount",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub source_amount: Option<Amount>,
 
     /// The currency (ISO 3166-1 alpha-3) to withdrawal from the source wallet.
@@ -25,60 +33,145 @@ pub struct CreateTransfer {
     /// "bitcoin:[address]". Note: cellphone numbers are assumed to be a US
     /// number, for international numbers include a '+' and the country code as
     /// the prefix.
-    pub dest: String,
+    pub dest: SystemResourceName,
 
     /// Specifies the total amount of currency to deposit (as defined in
     /// `depositCurrency`). Only include `sourceAmount` OR `destAmount`, not
     /// both.
+    #[serde(
+        serialize_with = "serialize_plain_amount",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub dest_amount: Option<Amount>,
 
     /// The currency (ISO 3166-1 alpha-3) to deposit. if not provided, the
     /// deposit will be the same as the withdrawal currency (no exchange
     /// performed).
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub dest_currency: Option<Currency>,
 
     /// An optional user visible message to be sent with the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 
     /// An optional url that Wyre will POST a status callback to (see [Callbacks](https://docs.sendwyre.com/v3/docs/subscribe-webhook)
-    /// for more information).
-    pub notify_url: Option<String>,
+    /// for more information). Must be an `https://` url.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_url: Option<Url>,
 
     /// An optional parameter to automatically confirm the transfer order.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub auto_confirm: Option<bool>,
 
     /// An optional custom ID to tag the transfer.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_id: Option<String>,
 
     /// When true, the amount indicated (source or dest) will be treated as
-    /// already including the fees.
+    /// already including the fees. Fees are always charged in the source
+    /// currency, so this decides which side absorbs them: with
+    /// `sourceAmount` set, `true` takes the fees out of what is converted and
+    /// `false` withdraws them on top; with `destAmount` set, `true` deducts
+    /// the fees from what arrives and `false` withdraws them on top. See
+    /// [`with_fees_on_source`](CreateTransfer::with_fees_on_source) and
+    /// [`with_fees_on_dest`](CreateTransfer::with_fees_on_dest).
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub amount_includes_fees: Option<bool>,
 
     /// Creates a quote transfer object, but does not execute a real transfer.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub preview: Option<bool>,
 
     /// When true, disables outbound emails/messages to the destination.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mute_messages: Option<bool>,
+
+    /// When true, applies the discount of the partner's rewards program to
+    /// the transfer fees.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards_discount: Option<bool>,
+
+    /// The account that referred the transfer, used to attribute it in
+    /// partner programs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrer_account_id: Option<AccountId>,
+
+    /// The id of a reservation that locked the exchange rate of a quote, so
+    /// the transfer executes at the rate that was shown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservation: Option<String>,
+}
+
+impl CreateTransfer {
+    /// Has the source pay the fees, so that the dest receives the full
+    /// converted amount. With `source_amount` set, the fees are withdrawn on
+    /// top of it; with `dest_amount` set, exactly that amount arrives.
+    #[must_use]
+    pub fn with_fees_on_source(mut self) -> CreateTransfer {
+        self.amount_includes_fees = Some(false);
+        self
+    }
+
+    /// Has the dest bear the fees, so that the source is charged no more than
+    /// the amount given. With `source_amount` set, exactly that amount is
+    /// withdrawn; with `dest_amount` set, the fees are deducted from it.
+    #[must_use]
+    pub fn with_fees_on_dest(mut self) -> CreateTransfer {
+        self.amount_includes_fees = Some(true);
+        self
+    }
+
+    /// Checks the request for problems that the Wyre API would otherwise
+    /// reject (or silently ignore) after it has been sent.
+    ///
+    /// `message` and `custom_id` aren't checked: Wyre doesn't publish length
+    /// or character limits for them, and a guessed limit could reject
+    /// transfers Wyre would accept. Being `String`s, they're always valid
+    /// UTF-8.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(notify_url) = &self.notify_url {
+            if notify_url.scheme() != "https" {
+                return Err(ValidationError(format!(
+                    "notify_url must be an https url: {}",
+                    notify_url
+                )));
+            }
+        }
+
+        let dest_currency = self.dest_currency.unwrap_or(self.source_currency);
+        if let Some(currencies) = self.dest.compatible_currencies() {
+            if dest_currency != Currency::Other && !currencies.contains(&dest_currency) {
+                return Err(ValidationError(format!(
+                    "{:?} can't be sent to {}",
+                    dest_currency, self.dest
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub struct Transfer {
-    pub id: String,
+    pub id: TransferId,
     pub owner: String,
-    pub source: String,
+    pub source: SystemResourceName,
     pub source_amount: Amount,
     pub source_currency: Currency,
-    pub dest: String,
+    pub dest: SystemResourceName,
     pub dest_amount: Amount,
     pub dest_currency: Currency,
+    pub dest_name: Option<String>,
     pub status: TransferStatus,
     // pub status_histories: ???,
-    pub pending_sub_status: Option<String>,
+    pub pending_sub_status: Option<PendingSubStatus>,
     // pub failure_reason: ???,
     // pub reversal_reason: ???,
     // pub reversing_sub_status: ???,
+    pub created_at: u64,
     pub completed_at: Option<u64>,
     pub updated_at: Option<u64>,
     pub cancelled_at: Option<u64>,
@@ -86,54 +179,262 @@ pub struct Transfer {
     pub exchange_rate: Option<f32>,
     pub fees: HashMap<Currency, Amount>,
     pub total_fees: f32,
-    // pub blockchain_tx: ???,
+    pub blockchain_tx: Option<BlockchainTx>,
     pub message: Option<String>,
     pub custom_id: Option<String>,
 }
 
-/// See [Transfer Lifecycle](https://docs.sendwyre.com/docs/transfer-resources#transfer-lifecycle)
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum TransferStatus {
-    /// A preview transfer. These transfers cannot be confirmed and funds will
-    /// never move on them. They're created by specifying the 'preview=true'
-    /// parameter at time of transfer creation.
-    Preview,
-
-    /// A transfer with a valid quote. This is the default state for newly
-    /// created transfers. These transfers must be confirmed before they're
-    /// executed. Transfers will wait `UNCONFIRMED` for some about of time,
-    /// after which if they are sill `UNCONFIRMED` they will transition to
-    /// `EXPIRED`.
-    Unconfirmed,
-
-    /// A transfer in the pending state means we're working on moving the money
-    /// to its destination. (It does not require any further action from your
-    /// side).
-    Pending,
-
-    /// Once a transfer is fully executed and the funds have been confirmed at
-    /// the destination its status will change to `COMPLETED`.
-    Completed,
-
-    /// Any `UNCONFIRMED` transfer that is not confirmed inside their 30-second
-    /// confirmation window will transition to `EXPIRED`.
-    Expired,
-
-    /// If a transfer cannot be completed for any reason its status will change
-    /// to `FAILED`. If there's anything we can do to make sure the transfer
-    /// goes through we will reach out via support channels before failing a
-    /// transfer.
-    Failed,
-
-    /// If a transfer is reversed at a later time for any reason its status
-    /// will change to `REVERSED`. This happens with ACH payouts, for example,
-    /// where Wyre's banking partner may notify Wyre at a later time.
-    Reversed,
+/// The blockchain transaction that moved a transfer's funds on or off chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockchainTx {
+    /// The id of the transaction at Wyre, e.g. `BTX_XXXXXXXX`.
+    pub id: String,
+
+    /// The hash of the transaction on its network, e.g. to link to a block
+    /// explorer.
+    pub network_tx_id: Option<String>,
+
+    /// The currency that was sent.
+    pub currency: Option<Currency>,
+
+    /// How many confirmations the transaction had when the transfer was read.
+    pub confirmations: Option<u64>,
 }
 
-impl Default for TransferStatus {
-    fn default() -> Self {
-        TransferStatus::Pending
+impl Transfer {
+    /// Flattens the transfer into a record suitable for bookkeeping exports.
+    #[must_use]
+    pub fn to_ledger_entry(&self) -> LedgerEntry {
+        LedgerEntry {
+            timestamp: self.completed_at.unwrap_or(self.created_at),
+            transfer_id: self.id.clone(),
+            source: self.source.clone(),
+            source_amount: self.source_amount.clone(),
+            source_currency: self.source_currency,
+            dest: self.dest.clone(),
+            dest_amount: self.dest_amount.clone(),
+            dest_currency: self.dest_currency,
+            total_fees: f32_to_amount(self.total_fees).unwrap_or_default(),
+            exchange_rate: self.exchange_rate.and_then(f32_to_amount),
+            status: self.status.clone(),
+            custom_id: self.custom_id.clone(),
+        }
+    }
+
+    /// Reconstructs the request for this transfer, e.g. to execute a transfer
+    /// that was quoted with `preview`. The request sets `source_amount` with
+    /// `amount_includes_fees`, since the quoted source amount already
+    /// includes the fees, so the same amount is withdrawn.
+    ///
+    /// Confirmation and notification settings aren't part of a transfer and
+    /// are left unset. Neither is the reservation the quote was made with:
+    /// Wyre doesn't return it on the transfer, so set
+    /// [`reservation`](CreateTransfer::reservation) again to keep the quoted
+    /// rate.
+    #[must_use]
+    pub fn as_create_transfer(&self) -> CreateTransfer {
+        CreateTransfer {
+            source: self.source.clone(),
+            source_amount: Some(self.source_amount.clone()),
+            source_currency: self.source_currency,
+            dest: self.dest.clone(),
+            dest_amount: None,
+            dest_currency: Some(self.dest_currency),
+            message: self.message.clone(),
+            notify_url: None,
+            auto_confirm: None,
+            custom_id: self.custom_id.clone(),
+            amount_includes_fees: Some(true),
+            preview: None,
+            mute_messages: None,
+            rewards_discount: None,
+            referrer_account_id: None,
+            reservation: None,
+        }
     }
 }
+
+/// Converts a float Wyre sent to the shortest decimal that reads back as
+/// it, e.g. `0.2` rather than `0.200000003`. `None` for NaN or infinity.
+fn f32_to_amount(value: f32) -> Option<Amount> {
+    value.to_string().parse().ok()
+}
+
+/// A flat, serializable record of a transfer (e.g. for writing to a CSV),
+/// see [`Transfer::to_ledger_entry`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntry {
+    /// When the transfer completed, or when it was created if it hasn't
+    /// completed yet.
+    pub timestamp: u64,
+
+    /// The id of the transfer.
+    pub transfer_id: TransferId,
+
+    /// The SRN the funds were withdrawn from.
+    pub source: SystemResourceName,
+
+    /// The amount withdrawn from the source.
+    pub source_amount: Amount,
+
+    /// The currency withdrawn from the source.
+    pub source_currency: Currency,
+
+    /// The SRN the funds were deposited to.
+    pub dest: SystemResourceName,
+
+    /// The amount deposited to the destination.
+    pub dest_amount: Amount,
+
+    /// The currency deposited to the destination.
+    pub dest_currency: Currency,
+
+    /// The total fees charged for the transfer.
+    pub total_fees: Amount,
+
+    /// The exchange rate used, if the currencies differ.
+    pub exchange_rate: Option<Amount>,
+
+    /// The status of the transfer.
+    pub status: TransferStatus,
+
+    /// The custom id the transfer was tagged with.
+    pub custom_id: Option<String>,
+}
+
+/// A slimmed-down transfer for forwarding to clients, e.g. mobile apps.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferSummary {
+    /// The id of the transfer.
+    pub id: TransferId,
+
+    /// The status of the transfer.
+    pub status: TransferStatus,
+
+    /// The amount withdrawn from the source.
+    pub source_amount: Amount,
+
+    /// The currency withdrawn from the source.
+    pub source_currency: Currency,
+
+    /// The amount deposited to the destination.
+    pub dest_amount: Amount,
+
+    /// The currency deposited to the destination.
+    pub dest_currency: Currency,
+
+    /// When the transfer was created.
+    pub created_at: u64,
+
+    /// When the transfer completed, if it has.
+    pub completed_at: Option<u64>,
+}
+
+impl<'a> From<&'a Transfer> for TransferSummary {
+    fn from(transfer: &'a Transfer) -> Self {
+        TransferSummary {
+            id: transfer.id.clone(),
+            status: transfer.status.clone(),
+            source_amount: transfer.source_amount.clone(),
+            source_currency: transfer.source_currency,
+            dest_amount: transfer.dest_amount.clone(),
+            dest_currency: transfer.dest_currency,
+            created_at: transfer.created_at,
+            completed_at: transfer.completed_at,
+        }
+    }
+}
+
+open_enum! {
+    /// See [Transfer Lifecycle](https://docs.sendwyre.com/docs/transfer-resources#transfer-lifecycle)
+    #[derive(Default)]
+    pub enum TransferStatus {
+        /// A preview transfer. These transfers cannot be confirmed and funds will
+        /// never move on them. They're created by specifying the 'preview=true'
+        /// parameter at time of transfer creation.
+        Preview = "PREVIEW",
+
+        /// A transfer with a valid quote. This is the default state for newly
+        /// created transfers. These transfers must be confirmed before they're
+        /// executed. Transfers will wait `UNCONFIRMED` for some about of time,
+        /// after which if they are sill `UNCONFIRMED` they will transition to
+        /// `EXPIRED`.
+        Unconfirmed = "UNCONFIRMED",
+
+        /// A transfer in the pending state means we're working on moving the money
+        /// to its destination. (It does not require any further action from your
+        /// side).
+        #[default]
+        Pending = "PENDING",
+
+        /// Once a transfer is fully executed and the funds have been confirmed at
+        /// the destination its status will change to `COMPLETED`.
+        Completed = "COMPLETED",
+
+        /// Any `UNCONFIRMED` transfer that is not confirmed inside their 30-second
+        /// confirmation window will transition to `EXPIRED`.
+        Expired = "EXPIRED",
+
+        /// If a transfer cannot be completed for any reason its status will change
+        /// to `FAILED`. If there's anything we can do to make sure the transfer
+        /// goes through we will reach out via support channels before failing a
+        /// transfer.
+        Failed = "FAILED",
+
+        /// If a transfer is reversed at a later time for any reason its status
+        /// will change to `REVERSED`. This happens with ACH payouts, for example,
+        /// where Wyre's banking partner may notify Wyre at a later time.
+        Reversed = "REVERSED",
+    }
+}
+
+open_enum! {
+    /// Why a [`TransferStatus::Pending`] transfer is still pending.
+    pub enum PendingSubStatus {
+        /// Waiting on the funds from the source, e.g. an ACH debit to settle.
+        AwaitingFunds = "AWAITING_FUNDS",
+
+        /// The funds are in, waiting to be sent to the destination.
+        AwaitingSend = "AWAITING_SEND",
+
+        /// The transfer is being sent to the destination.
+        Processing = "PROCESSING",
+    }
+}
+
+impl TransferStatus {
+    /// Returns `true` once the transfer won't progress any further
+    /// (`COMPLETED`, `FAILED`, `REVERSED` or `EXPIRED`).
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        use TransferStatus::*;
+
+        matches!(self, Completed | Failed | Reversed | Expired)
+    }
+
+    /// Returns `true` if the funds arrived at the destination (`COMPLETED`).
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        *self == TransferStatus::Completed
+    }
+
+    /// Returns `true` while Wyre is moving the funds (`PENDING`).
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        *self == TransferStatus::Pending
+    }
+}
+
+/// A page of transfers, see [`Client::list_transfers`](crate::Client::list_transfers).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct TransferList {
+    pub data: Vec<Transfer>,
+    pub records_total: usize,
+    pub position: usize,
+    pub records_filtered: usize,
+}
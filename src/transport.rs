@@ -0,0 +1,44 @@
+use std::fmt::Debug;
+
+use futures03::future::BoxFuture;
+use http::{Request, Response};
+
+use crate::error::Error;
+
+/// Sends the requests built by a [`Client`](crate::Client), see
+/// [`Client::with_transport`](crate::Client::with_transport).
+///
+/// Requests and responses are the `http` crate's types with the whole body
+/// in memory, so any HTTP stack can be plugged in. The default transport is
+/// a `reqwest::Client`, behind the `reqwest` feature.
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Sends the request and returns the response, whatever its status.
+    fn execute(&self, request: Request<Vec<u8>>)
+        -> BoxFuture<'_, Result<Response<Vec<u8>>, Error>>;
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpTransport for reqwest::Client {
+    fn execute(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> BoxFuture<'_, Result<Response<Vec<u8>>, Error>> {
+        use std::convert::TryFrom;
+
+        Box::pin(async move {
+            let request = reqwest::Request::try_from(request)?;
+            let response = reqwest::Client::execute(self, request).await?;
+
+            let status = response.status();
+            let version = response.version();
+            let headers = response.headers().clone();
+
+            let mut response = Response::new(response.bytes().await?.to_vec());
+            *response.status_mut() = status;
+            *response.version_mut() = version;
+            *response.headers_mut() = headers;
+
+            Ok(response)
+        })
+    }
+}
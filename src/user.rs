@@ -2,28 +2,53 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::Address;
+use crate::common::open_enum;
+use crate::{Address, Amount, Currency, SystemResourceName, UserId};
 
 /// A Wyre User object indicating approval status
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
     /// The Wyre id of the user
-    pub id: String,
+    pub id: UserId,
     /// The [approval status](ApprovalStatus) of the user
     pub status: UserStatus,
+    /// The id of the partner the user belongs to
+    pub partner_id: String,
+    /// Whether the user is an individual or a business
+    #[serde(rename = "type")]
+    pub kind: UserType,
     /// The time the user was created at
     pub created_at: i64,
     /// The user's cryptocurrency deposit addresses
-    pub deposit_addresses: DepositAddresses,
-    /// The user's cryptocurrency total balances
-    pub total_balances: UserBalances,
-    /// The user's cryptocurrency available balances
-    pub available_balances: UserBalances,
+    pub deposit_addresses: HashMap<Currency, String>, // currency => address
+    /// The user's total balances
+    pub total_balances: HashMap<Currency, Amount>,
+    /// The user's available balances
+    pub available_balances: HashMap<Currency, Amount>,
     /// The status of the user's fields
     pub fields: HashMap<UserFieldId, UserField>,
 }
 
+impl User {
+    /// The SRN of the user, e.g. for masquerading as them.
+    #[must_use]
+    pub fn srn(&self) -> SystemResourceName {
+        SystemResourceName::User(self.id.to_string())
+    }
+}
+
+/// The type of a [`User`], mirroring [`AccountType`](crate::AccountType)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UserType {
+    /// A person
+    Individual,
+
+    /// A business entity
+    Business,
+}
+
 /// The field IDs your specific integration has to support depend on your
 /// [integration type](https://docs.sendwyre.com/docs/users#integration-options).
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -82,37 +107,12 @@ pub enum UserFieldType {
     Address(Option<Address>),
 }
 
-/// Balances of user cryptocurrencies
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub struct UserBalances {
-    /// Bitcoin
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub btc: Option<i64>,
-    /// Ethereum
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub eth: Option<f64>,
-}
-
-/// Blockchain addresses for deposit
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub struct DepositAddresses {
-    /// Ethereum
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub eth: Option<String>,
-    /// Bitcoin
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub btc: Option<String>,
-}
-
 /// Values used for the `create_user` and `update_user` methods
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ModifyUser {
-    /// List of blockchains to connect the user to. Supported: `BTC`, `ETH`, `ALL`. Defaults to
-    /// none (empty list).
-    pub blockchains: Vec<String>,
+    /// List of blockchains to connect the user to. Defaults to none (empty list).
+    pub blockchains: Vec<Blockchain>,
     /// If true, returns immediately. This skips the default behavior of waiting up to 5 seconds
     /// for processing to complete, and so will always result in a PENDING user.
     pub immediate: bool,
@@ -123,29 +123,126 @@ pub struct ModifyUser {
     pub scopes: Vec<UserScope>,
 }
 
-/// The KYC status of a user
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum UserStatus {
-    /// The User has been closed and may not transact.
-    Closed,
+impl ModifyUser {
+    /// Starts building a [`ModifyUser`] with typed setters for each field.
+    #[must_use]
+    pub fn builder() -> ModifyUserBuilder {
+        ModifyUserBuilder::default()
+    }
+}
 
-    /// Waiting on action from you or the User. This is the initial
-    /// state before all information has been submitted, or after any has
-    /// failed to pass verifications.
-    Open,
+/// Builds a [`ModifyUser`], pairing every field id with the right field type.
+/// See [`ModifyUser::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct ModifyUserBuilder {
+    inner: ModifyUser,
+}
+
+impl ModifyUserBuilder {
+    /// Sets [`UserFieldId::FirstName`].
+    #[must_use]
+    pub fn first_name(self, first_name: impl Into<String>) -> Self {
+        self.string_field(UserFieldId::FirstName, first_name.into())
+    }
 
-    /// Information has been fully submitted and is waiting on review from
-    /// Wyre. The User cannot yet transact.
-    Pending,
+    /// Sets [`UserFieldId::LastName`].
+    #[must_use]
+    pub fn last_name(self, last_name: impl Into<String>) -> Self {
+        self.string_field(UserFieldId::LastName, last_name.into())
+    }
 
-    /// Information has been reviewed and accepted by Wyre. The User is now
-    /// approved to transact.
-    ///
-    /// The `UserStatus` being `Approved` does not mean the user is able to submit transactions.
-    /// The user status is used for compliance reasons. The `status` field of all [`UserField`]s
-    /// need to be [`Submitted`](UserFieldStatus) before a user is ready to transact.
-    Approved,
+    /// Sets [`UserFieldId::Email`].
+    #[must_use]
+    pub fn email(self, email: impl Into<String>) -> Self {
+        self.string_field(UserFieldId::Email, email.into())
+    }
+
+    /// Sets [`UserFieldId::DateOfBirth`], formatted as `YYYY-MM-DD`.
+    #[must_use]
+    pub fn date_of_birth(self, date_of_birth: impl Into<String>) -> Self {
+        self.string_field(UserFieldId::DateOfBirth, date_of_birth.into())
+    }
+
+    /// Sets [`UserFieldId::ResidenceAddress`].
+    #[must_use]
+    pub fn residence_address(mut self, address: Address) -> Self {
+        self.inner.fields.insert(
+            UserFieldId::ResidenceAddress,
+            UserFieldType::Address(Some(address)),
+        );
+        self
+    }
+
+    /// Adds a scope to bias the returned view with.
+    #[must_use]
+    pub fn scope(mut self, scope: UserScope) -> Self {
+        self.inner.scopes.push(scope);
+        self
+    }
+
+    /// Adds a blockchain to connect the user to.
+    #[must_use]
+    pub fn blockchain(mut self, blockchain: Blockchain) -> Self {
+        self.inner.blockchains.push(blockchain);
+        self
+    }
+
+    /// See [`ModifyUser::immediate`].
+    #[must_use]
+    pub fn immediate(mut self, immediate: bool) -> Self {
+        self.inner.immediate = immediate;
+        self
+    }
+
+    /// Returns the built [`ModifyUser`].
+    #[must_use]
+    pub fn build(self) -> ModifyUser {
+        self.inner
+    }
+
+    fn string_field(mut self, id: UserFieldId, value: String) -> Self {
+        self.inner
+            .fields
+            .insert(id, UserFieldType::String(Some(value)));
+        self
+    }
+}
+
+open_enum! {
+    /// Blockchains a user can be connected to (see [`ModifyUser::blockchains`])
+    pub enum Blockchain {
+        /// Bitcoin
+        Btc = "BTC",
+        /// Ethereum
+        Eth = "ETH",
+        /// Every supported blockchain
+        All = "ALL",
+    }
+}
+
+open_enum! {
+    /// The KYC status of a user
+    pub enum UserStatus {
+        /// The User has been closed and may not transact.
+        Closed = "CLOSED",
+
+        /// Waiting on action from you or the User. This is the initial
+        /// state before all information has been submitted, or after any has
+        /// failed to pass verifications.
+        Open = "OPEN",
+
+        /// Information has been fully submitted and is waiting on review from
+        /// Wyre. The User cannot yet transact.
+        Pending = "PENDING",
+
+        /// Information has been reviewed and accepted by Wyre. The User is now
+        /// approved to transact.
+        ///
+        /// The `UserStatus` being `Approved` does not mean the user is able to submit transactions.
+        /// The user status is used for compliance reasons. The `status` field of all [`UserField`]s
+        /// need to be [`Submitted`](UserFieldStatus) before a user is ready to transact.
+        Approved = "APPROVED",
+    }
 }
 
 /// The current approval status of a user field
@@ -163,7 +260,7 @@ pub enum UserFieldStatus {
 }
 
 /// User scopes (currently only [`Transfer`](UserScopes::Transfer) is supported)
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum UserScope {
     /// General access to the Transfers API. Access to this scope is required for all transfers.
@@ -179,13 +276,13 @@ pub enum UserScope {
     /// For higher limit card purchases, full KYC including ID verification.
     DebitCardL2,
 }
-impl ToString for UserScope {
-    fn to_string(&self) -> String {
+
+impl std::fmt::Display for UserScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            UserScope::Transfer => "TRANSFER",
-            UserScope::ACH => "ACH",
-            UserScope::DebitCardL2 => "DEBIT_CARD_L2",
+            UserScope::Transfer => write!(f, "TRANSFER"),
+            UserScope::ACH => write!(f, "ACH"),
+            UserScope::DebitCardL2 => write!(f, "DEBIT_CARD_L2"),
         }
-        .to_owned()
     }
 }